@@ -1,16 +1,29 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use minifb::{Key, Window, WindowOptions};
+use minifb::{Window, WindowOptions};
 
-use crate::camera::Camera;
+use crate::bindings::Bindings;
+use crate::calendar;
+use crate::camera::{self, Camera};
+use crate::capture::{self, Recorder};
+use crate::cli::Options;
 use crate::collision;
+use crate::hud::{Hud, HudContext};
 use crate::input::InputState;
+use crate::labels::Labels;
 use crate::math::Vec3;
+use crate::orbit_camera::OrbitRig;
 use crate::renderer::Renderer;
-use crate::skybox;
+use crate::sim_clock::SimClock;
 use crate::texture::Texture;
-use crate::warp::WarpState;
-use crate::world::SolarSystem;
+use crate::timestep::{self, FixedTimestep, Snapshot};
+use crate::warp::{self, WarpState};
+use crate::view;
+use crate::world::{BodyKind, Integrator, Scene, SolarSystem};
+
+const WINDOW_TITLE: &str = "Sistema Solar - Rust Software Renderer";
+/// Cada cuántos segundos se registra la deriva en modo N-cuerpos
+const DRIFT_LOG_INTERVAL: f32 = 5.0;
 
 pub struct App {
     window: Window,
@@ -19,101 +32,369 @@ pub struct App {
     camera: Camera,
     input: InputState,
     last_frame: Instant,
+    clock: SimClock,
+    recorder: Recorder,
+    hud: Hud,
+    labels: Labels,
+    /// Cuerpo elegido con el mouse (lo usan HUD, warp y seguimiento)
+    selected: Option<usize>,
+    /// Modo órbita: la cámara sigue a un cuerpo (None = vuelo libre)
+    orbit: Option<OrbitRig>,
     running: bool,
     warp: WarpState,
+    /// Integrador que usa el modo N-cuerpos al activarlo
+    integrator: Integrator,
+    /// Segundos reales desde el último registro de deriva en la consola
+    drift_log_timer: f32,
+    timestep: FixedTimestep,
+    /// Estado antes del último paso fijo, para interpolar al dibujar
+    previous: Snapshot,
 
-    // Texturas
-    textura_sol: Texture,
-    textura_planeta1: Texture,
-    textura_planeta2: Texture,
-    textura_planeta3: Texture,
-    textura_planeta4: Texture,
-    textura_luna: Texture,
-    textura_cielo: Texture,
+    // Fondo de estrellas de la escena
+    skybox: Option<Texture>,
 }
 
 impl App {
-    pub fn new(width: usize, height: usize) -> Self {
-        let window = Window::new(
-            "Sistema Solar - Rust Software Renderer",
+    pub fn new(opts: &Options, scene: Scene, bindings: Bindings) -> Self {
+        let (width, height) = (opts.width, opts.height);
+        let mut window = Window::new(
+            WINDOW_TITLE,
             width,
             height,
             WindowOptions::default(),
         )
         .expect("No se pudo crear la ventana");
+        // minifb espera en `update_with_buffer` lo que falte para el tope de FPS
+        window.limit_update_rate(
+            (opts.fps > 0).then(|| Duration::from_secs_f32(1.0 / opts.fps as f32)),
+        );
 
         let renderer = Renderer::new(width, height);
-        let system = SolarSystem::new_demo();
-        let camera = Camera::new();
+        let mut system = scene.system;
+        if opts.nbody {
+            system.start_nbody(opts.integrator);
+        }
+        let mut camera = Camera::new();
+        if let Some(position) = scene.camera {
+            camera.position = position;
+            camera.look_at(Vec3::zero());
+        }
+        let previous = Snapshot::capture(&system, &camera);
 
-        let textura_sol = Texture::from_file("assets/2k_sun.jpg");
-        let textura_planeta1 = Texture::from_file("assets/2k_mercury.jpg");
-        let textura_planeta2 = Texture::from_file("assets/2k_venus_atmosphere.jpg");
-        let textura_planeta3 = Texture::from_file("assets/2k_earth_daymap.jpg");
-        let textura_planeta4 = Texture::from_file("assets/2k_moon.jpg");
-        let textura_luna = Texture::from_file("assets/2k_mars.jpg");
-        let textura_cielo = Texture::from_file("assets/2k_stars.jpg");
+        let mut input = InputState::new(bindings);
+        input.sensibilidad_raton = opts.mouse_sensitivity.to_radians();
+        input.invertir_raton = opts.invert_mouse;
 
         Self {
             window,
             renderer,
             system,
             camera,
-            input: InputState::new(),
+            input,
             last_frame: Instant::now(),
+            clock: SimClock::new(),
+            recorder: Recorder::new(),
+            hud: Hud::new(),
+            labels: Labels::new(),
+            selected: None,
+            orbit: None,
             running: true,
             warp: WarpState::new(),
-            textura_sol,
-            textura_planeta1,
-            textura_planeta2,
-            textura_planeta3,
-            textura_planeta4,
-            textura_luna,
-            textura_cielo,
+            integrator: opts.integrator,
+            drift_log_timer: 0.0,
+            timestep: FixedTimestep::new(),
+            previous,
+            skybox: scene.skybox,
         }
     }
 
     pub fn run(&mut self) {
-        while self.running && self.window.is_open() && !self.window.is_key_down(Key::Escape) {
+        while self.running && self.window.is_open() {
             let now = Instant::now();
-            let dt = (now - self.last_frame).as_secs_f32();
+            let frame_dt = (now - self.last_frame).as_secs_f32();
             self.last_frame = now;
 
             self.input.update(&self.window);
-            self.update(dt);
-            self.render();
+            self.handle_input();
+
+            // Al grabar cada frame avanza un tiempo fijo, sin importar lo que tarde,
+            // y se dibuja el último paso tal cual
+            let (steps, alpha) = if self.recorder.is_recording() {
+                let steps = (capture::RECORD_DT / timestep::FIXED_DT).round() as usize;
+                (steps, 1.0)
+            } else {
+                let steps = self.timestep.advance(frame_dt);
+                (steps, self.timestep.alpha())
+            };
+
+            for _ in 0..steps {
+                self.previous = Snapshot::capture(&self.system, &self.camera);
+                self.step(timestep::FIXED_DT);
+                self.input.mirar_raton = (0.0, 0.0);
+            }
 
-            std::thread::sleep(std::time::Duration::from_millis(5));
+            let step_time = steps as f32 * timestep::FIXED_DT;
+            self.hud.update(frame_dt, step_time, &self.camera);
+            self.render(alpha);
+            self.handle_capture();
         }
     }
 
-    fn update(&mut self, dt: f32) {
+    /// Acciones de una sola vez (alternar modos, saltos, selección...): se
+    /// atienden una vez por frame, haya o no pasos de simulación.
+    fn handle_input(&mut self) {
+        // Con el diálogo de fecha abierto el teclado es solo suyo
+        if self.input.escribiendo_fecha {
+            if let Some(text) = self.input.fecha_confirmada.take() {
+                self.jump_to_date(&text);
+            }
+            return;
+        }
+
+        if self.input.salir {
+            self.running = false;
+        }
+        if self.input.alternar_hud {
+            self.hud.visible = !self.hud.visible;
+        }
+        if self.input.alternar_etiquetas {
+            self.labels.visible = !self.labels.visible;
+        }
+
+        if self.input.alternar_raton {
+            // Sin cursor visible mientras el mouse maneja la cámara
+            self.window.set_cursor_visibility(!self.input.raton_capturado);
+        }
+        if self.input.alternar_nave {
+            self.camera.toggle_ship();
+        }
+        if self.input.alternar_asistencia
+            && let Some(ship) = &mut self.camera.ship
+        {
+            ship.flight_assist = !ship.flight_assist;
+        }
+        if self.input.zoom != 0 {
+            self.camera.zoom(self.input.zoom);
+        }
+        if self.input.restablecer_zoom {
+            self.camera.fov_y = camera::DEFAULT_FOV.to_radians();
+        }
+
+        if self.input.alternar_diagnostico {
+            self.hud.diagnostics = !self.hud.diagnostics;
+        }
+        if self.input.alternar_gravedad {
+            if self.system.nbody.is_some() {
+                self.log_drift();
+                self.system.stop_nbody();
+                println!("Gravedad mutua desactivada: órbitas de Kepler");
+            } else {
+                self.system.start_nbody(self.integrator);
+                self.drift_log_timer = 0.0;
+                println!("Gravedad mutua activada ({})", self.integrator.name());
+            }
+        }
+
+        self.handle_time_controls();
+        self.handle_selection();
         self.handle_warps();
+        self.handle_orbit_toggle();
+    }
+
+    /// Un paso fijo de `dt` segundos reales: cámara, simulación y colisiones
+    fn step(&mut self, dt: f32) {
+        // Inicio del paso para la prueba de colisión continua (después de los
+        // saltos instantáneos, que no recorren el camino)
+        let camera_before = self.camera.position;
+        let bodies_before = collision::snapshot(&self.system);
+
+        // Durante un viaje la cámara no responde al teclado. Con el diálogo de
+        // fecha abierto sí se actualiza (la nave conserva su inercia), pero
+        // `InputState` ya soltó las teclas de vuelo.
+        if !self.warp.active {
+            match &mut self.orbit {
+                Some(rig) => rig.update(dt, &self.input),
+                None => self.camera.update(dt, &self.input),
+            }
+        }
+
+        let sim_dt = self.clock.advance(dt);
+        self.system.update(sim_dt);
 
+        // Viajes y modo órbita siguen la posición ya actualizada del cuerpo;
+        // mientras mandan ellos la nave no conserva inercia
         if self.warp.active {
-            self.warp.update(dt, &mut self.camera);
-        } else {
-            self.camera.update(dt, &self.input);
+            self.warp.update(dt, &mut self.camera, &self.system);
+            self.camera.velocity = Vec3::zero();
+        } else if let Some(rig) = &self.orbit {
+            rig.apply(self.system.body_position(rig.target), &mut self.camera);
+            self.camera.velocity = Vec3::zero();
         }
 
-        self.system.update(dt);
+        if self.system.nbody.is_some() {
+            self.drift_log_timer += dt;
+            if self.drift_log_timer >= DRIFT_LOG_INTERVAL {
+                self.drift_log_timer = 0.0;
+                self.log_drift();
+            }
+        }
 
-        collision::resolve_collisions(&self.system, &mut self.camera);
+        let contact = collision::resolve_collisions(
+            &self.system,
+            &bodies_before,
+            sim_dt,
+            camera_before,
+            &mut self.camera,
+        );
+        // Un viaje que choca se corta ahí en vez de atravesar el cuerpo
+        if let Some(c) = contact
+            && self.warp.active
+        {
+            self.warp.active = false;
+            println!("Viaje interrumpido: choque con {}", self.system.bodies[c.body].name);
+        }
     }
 
-    fn handle_warps(&mut self) {
-        if self.input.warp_1 {
-            self.instant_warp_to_body(1);
+    /// Deja en la consola la deriva de energía y momento angular desde que se
+    /// activó la gravedad mutua
+    fn log_drift(&self) {
+        let drift = self.system.drift();
+        println!(
+            "t = {:.1} s  deriva energía {:.2e}  momento angular {:.2e}",
+            self.system.time, drift.energy, drift.angular_momentum
+        );
+    }
+
+    fn handle_time_controls(&mut self) {
+        let before = (self.clock.paused, self.clock.scale());
+
+        if self.input.pausa {
+            self.clock.toggle_pause();
+        }
+        if self.input.acelerar {
+            self.clock.speed_up();
         }
-        if self.input.warp_2 {
-            self.instant_warp_to_body(2);
+        if self.input.desacelerar {
+            self.clock.slow_down();
         }
-        if self.input.warp_3 {
-            self.instant_warp_to_body(3);
+        if self.input.invertir_tiempo {
+            self.clock.toggle_reverse();
+        }
+        if self.input.paso_simple {
+            self.clock.step();
+        }
+
+        if before != (self.clock.paused, self.clock.scale()) {
+            let estado = if self.clock.paused { " (pausa)" } else { "" };
+            self.window.set_title(&format!(
+                "{} - tiempo x{}{}",
+                WINDOW_TITLE,
+                self.clock.scale(),
+                estado
+            ));
+        }
+    }
+
+    /// Lleva la simulación a la fecha escrita en el diálogo
+    fn jump_to_date(&mut self, text: &str) {
+        match calendar::parse_date(text) {
+            Ok(jd) => {
+                self.system.set_date(jd);
+                // Los cuerpos saltan: no hay nada que interpolar ni que barrer
+                self.previous = Snapshot::capture(&self.system, &self.camera);
+                println!("Fecha: {}", calendar::format_date(jd));
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+
+    fn handle_capture(&mut self) {
+        if self.input.captura {
+            match self.recorder.screenshot(&self.renderer) {
+                Ok(path) => println!("Captura guardada en {}", path.display()),
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+
+        if self.input.grabar {
+            match self.recorder.toggle_recording() {
+                Ok(dir) if self.recorder.is_recording() => {
+                    println!("Grabando en {}", dir.display())
+                }
+                Ok(dir) => println!("Grabación terminada: {}", dir.display()),
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+
+        if let Err(e) = self.recorder.record_frame(&self.renderer) {
+            eprintln!("{}", e);
+            let _ = self.recorder.toggle_recording();
+        }
+    }
+
+    /// Clic izquierdo: lanza un rayo por el píxel y selecciona el cuerpo más
+    /// cercano que toca; un clic en el vacío quita la selección. Con el mouse
+    /// capturado el cursor está oculto, así que se apunta con la mira central.
+    fn handle_selection(&mut self) {
+        let Some((mut mx, mut my)) = self.input.clic else {
+            return;
+        };
+        if self.input.raton_capturado {
+            mx = self.renderer.width as f32 / 2.0;
+            my = self.renderer.height as f32 / 2.0;
+        }
+
+        let dir = self.renderer.screen_ray(mx, my, &self.camera);
+        self.selected = self
+            .system
+            .pick_body(self.camera.position, dir)
+            .map(|(i, _)| i);
+    }
+
+    /// C entra en órbita alrededor del cuerpo seleccionado (o el más cercano)
+    /// y vuelve a vuelo libre si ya estaba orbitando.
+    fn handle_orbit_toggle(&mut self) {
+        if !self.input.alternar_orbita {
+            return;
+        }
+        if self.orbit.take().is_some() {
+            return;
+        }
+
+        let target = self
+            .selected
+            .or_else(|| self.system.nearest_body(self.camera.position).map(|(i, _)| i));
+        if let Some(i) = target {
+            self.warp.active = false;
+            self.orbit = Some(OrbitRig::from_camera(
+                i,
+                self.system.body_position(i),
+                self.system.bodies[i].radius,
+                &self.camera,
+            ));
+        }
+    }
+
+    fn handle_warps(&mut self) {
+        if let Some(index) = self.input.warp_instantaneo {
+            self.instant_warp_to_body(index);
         }
 
         if self.input.warp_animated && !self.warp.active {
-            self.start_animated_warp(1);
+            // Sin selección, al planeta o luna más cercano
+            let target = self.selected.or_else(|| {
+                let camera = self.camera.position;
+                let system = &self.system;
+                (0..system.bodies.len())
+                    .filter(|&i| !matches!(system.bodies[i].kind, BodyKind::Star))
+                    .min_by(|&a, &b| {
+                        let dist = |i| (system.body_position(i) - camera).length();
+                        dist(a).total_cmp(&dist(b))
+                    })
+            });
+            if let Some(index) = target {
+                self.start_animated_warp(index);
+            }
         }
     }
 
@@ -122,60 +403,60 @@ impl App {
             return;
         }
         let center = self.system.body_position(index);
-        self.camera.position = center + Vec3::new(0.0, 20.0, 40.0);
+        let radius = self.system.bodies[index].radius;
+        self.camera.position = center + warp::arrival_offset(self.camera.position, center, radius);
+        self.camera.look_at(center);
+        self.camera.velocity = Vec3::zero();
+        self.warp.active = false;
+        self.orbit = None;
+        // Un salto no se interpola: el próximo frame ya se dibuja en destino
+        self.previous = Snapshot::capture(&self.system, &self.camera);
     }
 
     fn start_animated_warp(&mut self, index: usize) {
         if index >= self.system.bodies.len() {
             return;
         }
-        let center = self.system.body_position(index);
-        let target = center + Vec3::new(0.0, 20.0, 40.0);
-        let start = self.camera.position;
 
-        self.warp.start_animated(start, target, 1.8);
+        self.orbit = None;
+        self.warp.start_animated(&self.camera, &self.system, index);
     }
 
-    fn render(&mut self) {
-        self.renderer.clear(0x000000);
-
-        skybox::draw_skybox(&mut self.renderer, &self.camera, &self.textura_cielo);  
-
-        self.system.render(&mut self.renderer, &self.camera);
-
-        let mut body_indices: Vec<(usize, f32)> = Vec::new();
-        
-        for i in 0..self.system.bodies.len() {
-            let body_pos = self.system.body_position(i);
-            let distance = (body_pos - self.camera.position).length();
-            body_indices.push((i, distance));
-        }
-        
-        body_indices.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        
-        for (i, _) in body_indices {
-            if let Some(((sx, sy), radius_px)) =
-                self.system.project_body(i, &self.renderer, &self.camera)
-            {
-                let tex = match i {
-                    0 => &self.textura_sol,
-                    1 => &self.textura_planeta1,
-                    2 => &self.textura_planeta2,
-                    3 => &self.textura_planeta3,
-                    4 => &self.textura_planeta4,
-                    5 => &self.textura_luna,
-                    _ => continue,
-                };
-                
-                let rotation = self.system.bodies[i].angle;
-
-                self.renderer
-                    .draw_textured_sphere(tex, (sx, sy), radius_px, rotation);
-            }
-        }
+    /// Dibuja el estado interpolado entre los dos últimos pasos (`alpha` 0 =
+    /// anterior, 1 = actual) y después deja el estado actual como estaba.
+    fn render(&mut self, alpha: f32) {
+        let current = Snapshot::capture(&self.system, &self.camera);
+        self.previous
+            .lerp(&current, alpha)
+            .apply(&mut self.system, &mut self.camera);
+
+        view::draw_scene(
+            &mut self.renderer,
+            &self.system,
+            &self.camera,
+            self.skybox.as_ref(),
+        );
+
+        self.labels.draw(&mut self.renderer, &self.system, &self.camera);
+
+        self.hud.draw(
+            &mut self.renderer,
+            &HudContext {
+                camera: &self.camera,
+                system: &self.system,
+                clock: &self.clock,
+                recording: self.recorder.is_recording(),
+                selected: self.selected,
+                orbit_target: self.orbit.as_ref().map(|rig| rig.target),
+                crosshair: self.input.raton_capturado,
+                date_prompt: self.input.texto_fecha(),
+            },
+        );
 
         self.window
             .update_with_buffer(self.renderer.buffer(), self.renderer.width, self.renderer.height)
             .expect("Error al actualizar la ventana");
+
+        current.apply(&mut self.system, &mut self.camera);
     }
 }
@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+
+use minifb::{Key, KeyRepeat, Window};
+use serde::Deserialize;
+
+/// Archivo de controles que se carga si existe y no se pasa `--keys`
+pub const DEFAULT_BINDINGS: &str = "controles.toml";
+
+/// Acciones con nombre que se pueden asignar a teclas.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    MoveForward,
+    MoveBack,
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    LookLeft,
+    LookRight,
+    LookUp,
+    LookDown,
+    RollLeft,
+    RollRight,
+    ThrottleUp,
+    ThrottleDown,
+    Jump(usize),
+    WarpToSelection,
+    Pause,
+    SpeedUp,
+    SlowDown,
+    ReverseTime,
+    StepTime,
+    JumpToDate,
+    Screenshot,
+    Record,
+    ToggleHud,
+    ToggleLabels,
+    ToggleOrbit,
+    ToggleShip,
+    ToggleFlightAssist,
+    ToggleMouseLook,
+    ToggleGravity,
+    ToggleDiagnostics,
+    Quit,
+}
+
+/// Cómo se lee una acción en cada frame
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    /// Activa mientras la tecla esté apretada (movimiento)
+    Held,
+    /// Activa solo en el frame en que se aprieta (saltos, alternar modos)
+    Pressed,
+}
+
+use Action::*;
+use Trigger::*;
+
+/// Nombre en el archivo de controles, tipo de disparo y teclas por defecto
+const ACTIONS: &[(Action, &str, Trigger, &[Key])] = &[
+    (MoveForward, "mover_adelante", Held, &[Key::W]),
+    (MoveBack, "mover_atras", Held, &[Key::S]),
+    (MoveLeft, "mover_izquierda", Held, &[Key::A]),
+    (MoveRight, "mover_derecha", Held, &[Key::D]),
+    (MoveUp, "mover_arriba", Held, &[Key::E]),
+    (MoveDown, "mover_abajo", Held, &[Key::Q]),
+    (LookLeft, "mirar_izquierda", Held, &[Key::Left]),
+    (LookRight, "mirar_derecha", Held, &[Key::Right]),
+    (LookUp, "mirar_arriba", Held, &[Key::Up]),
+    (LookDown, "mirar_abajo", Held, &[Key::Down]),
+    (RollLeft, "girar_izquierda", Held, &[Key::Z]),
+    (RollRight, "girar_derecha", Held, &[Key::X]),
+    (ThrottleUp, "subir_potencia", Held, &[Key::LeftShift]),
+    (ThrottleDown, "bajar_potencia", Held, &[Key::LeftCtrl]),
+    (Jump(0), "salto_0", Pressed, &[Key::Key0]),
+    (Jump(1), "salto_1", Pressed, &[Key::Key1]),
+    (Jump(2), "salto_2", Pressed, &[Key::Key2]),
+    (Jump(3), "salto_3", Pressed, &[Key::Key3]),
+    (Jump(4), "salto_4", Pressed, &[Key::Key4]),
+    (Jump(5), "salto_5", Pressed, &[Key::Key5]),
+    (Jump(6), "salto_6", Pressed, &[Key::Key6]),
+    (Jump(7), "salto_7", Pressed, &[Key::Key7]),
+    (Jump(8), "salto_8", Pressed, &[Key::Key8]),
+    (Jump(9), "salto_9", Pressed, &[Key::Key9]),
+    (WarpToSelection, "viajar", Pressed, &[Key::Space]),
+    (Pause, "pausa", Pressed, &[Key::P]),
+    (SpeedUp, "acelerar", Pressed, &[Key::Equal, Key::NumPadPlus]),
+    (
+        SlowDown,
+        "desacelerar",
+        Pressed,
+        &[Key::Minus, Key::NumPadMinus],
+    ),
+    (ReverseTime, "invertir_tiempo", Pressed, &[Key::R]),
+    (StepTime, "paso_simple", Pressed, &[Key::N]),
+    (JumpToDate, "ir_a_fecha", Pressed, &[Key::T]),
+    (Screenshot, "captura", Pressed, &[Key::F12]),
+    (Record, "grabar", Pressed, &[Key::F9]),
+    (ToggleHud, "alternar_hud", Pressed, &[Key::H]),
+    (ToggleLabels, "alternar_etiquetas", Pressed, &[Key::L]),
+    (ToggleOrbit, "alternar_orbita", Pressed, &[Key::C]),
+    (ToggleShip, "alternar_nave", Pressed, &[Key::F]),
+    (
+        ToggleFlightAssist,
+        "alternar_asistencia",
+        Pressed,
+        &[Key::V],
+    ),
+    (ToggleMouseLook, "alternar_raton", Pressed, &[Key::M]),
+    (ToggleGravity, "alternar_gravedad", Pressed, &[Key::G]),
+    (ToggleDiagnostics, "alternar_diagnostico", Pressed, &[Key::I]),
+    (Quit, "salir", Pressed, &[Key::Escape]),
+];
+
+/// Teclas que se pueden nombrar en el archivo (con el nombre de minifb)
+#[rustfmt::skip]
+const KEYS: &[Key] = &[
+    Key::Key0, Key::Key1, Key::Key2, Key::Key3, Key::Key4, Key::Key5, Key::Key6, Key::Key7, Key::Key8, Key::Key9,
+    Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H, Key::I, Key::J, Key::K, Key::L, Key::M,
+    Key::N, Key::O, Key::P, Key::Q, Key::R, Key::S, Key::T, Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z,
+    Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6, Key::F7, Key::F8, Key::F9, Key::F10, Key::F11, Key::F12,
+    Key::Down, Key::Left, Key::Right, Key::Up,
+    Key::Apostrophe, Key::Backquote, Key::Backslash, Key::Comma, Key::Equal,
+    Key::LeftBracket, Key::Minus, Key::Period, Key::RightBracket, Key::Semicolon,
+    Key::Slash, Key::Backspace, Key::Delete, Key::End, Key::Enter, Key::Escape,
+    Key::Home, Key::Insert, Key::PageDown, Key::PageUp, Key::Space, Key::Tab,
+    Key::LeftShift, Key::RightShift, Key::LeftCtrl, Key::RightCtrl,
+    Key::LeftAlt, Key::RightAlt,
+    Key::NumPad0, Key::NumPad1, Key::NumPad2, Key::NumPad3, Key::NumPad4,
+    Key::NumPad5, Key::NumPad6, Key::NumPad7, Key::NumPad8, Key::NumPad9,
+    Key::NumPadDot, Key::NumPadSlash, Key::NumPadAsterisk, Key::NumPadMinus,
+    Key::NumPadPlus, Key::NumPadEnter,
+];
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BindingsFile {
+    #[serde(default)]
+    teclas: HashMap<String, KeyList>,
+}
+
+/// Una tecla suelta o una lista (`[]` deja la acción sin tecla)
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Mapa de acciones: qué teclas disparan cada acción.
+pub struct Bindings {
+    keys: HashMap<Action, Vec<Key>>,
+}
+
+impl Bindings {
+    /// Controles por defecto
+    pub fn new() -> Self {
+        let keys = ACTIONS
+            .iter()
+            .map(|&(action, _, _, keys)| (action, keys.to_vec()))
+            .collect();
+        Self { keys }
+    }
+
+    /// Carga un archivo de controles. Las acciones que no aparecen conservan
+    /// sus teclas por defecto.
+    pub fn load(path: &str) -> Result<Bindings, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("No se pudo leer {}: {}", path, e))?;
+        Bindings::parse(&text, path)
+    }
+
+    /// Lee controles desde el texto de un archivo; `path` solo aparece en
+    /// los mensajes de error
+    fn parse(text: &str, path: &str) -> Result<Bindings, String> {
+        let file: BindingsFile =
+            toml::from_str(text).map_err(|e| format!("Controles inválidos {}: {}", path, e))?;
+
+        let mut bindings = Bindings::new();
+        for (name, list) in file.teclas {
+            let action = ACTIONS
+                .iter()
+                .find(|&&(_, n, _, _)| n == name)
+                .map(|&(action, _, _, _)| action)
+                .ok_or_else(|| format!("{}: acción desconocida {}", path, name))?;
+
+            let names = match list {
+                KeyList::One(key) => vec![key],
+                KeyList::Many(keys) => keys,
+            };
+            let keys = names
+                .iter()
+                .map(|key| {
+                    parse_key(key)
+                        .ok_or_else(|| format!("{}: {}: tecla desconocida {}", path, name, key))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            bindings.keys.insert(action, keys);
+        }
+
+        Ok(bindings)
+    }
+
+    /// Si la acción está activa en este frame, según su tipo de disparo
+    pub fn active(&self, window: &Window, action: Action) -> bool {
+        let trigger = ACTIONS
+            .iter()
+            .find(|&&(a, _, _, _)| a == action)
+            .map_or(Held, |&(_, _, trigger, _)| trigger);
+        let keys = self.keys.get(&action).map_or(&[][..], |k| k.as_slice());
+
+        keys.iter().any(|&key| match trigger {
+            Held => window.is_key_down(key),
+            Pressed => window.is_key_pressed(key, KeyRepeat::No),
+        })
+    }
+}
+
+/// Nombre de tecla de minifb (`W`, `Space`, `Key1`, `LeftShift`...), sin
+/// distinguir mayúsculas
+fn parse_key(name: &str) -> Option<Key> {
+    KEYS.iter()
+        .copied()
+        .find(|key| format!("{:?}", key).eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(bindings: &Bindings, action: Action) -> &[Key] {
+        &bindings.keys[&action]
+    }
+
+    #[test]
+    fn parses_single_keys_and_lists() {
+        let text = r#"
+            [teclas]
+            mover_adelante = "Up"
+            pausa = ["Space", "p"]
+            captura = []
+        "#;
+        let bindings = Bindings::parse(text, "prueba.toml").unwrap();
+        assert_eq!(keys(&bindings, MoveForward), [Key::Up]);
+        assert_eq!(keys(&bindings, Pause), [Key::Space, Key::P]);
+        assert!(keys(&bindings, Screenshot).is_empty());
+        // Lo que no aparece conserva su tecla por defecto
+        assert_eq!(keys(&bindings, MoveBack), keys(&Bindings::new(), MoveBack));
+    }
+
+    #[test]
+    fn default_file_matches_defaults() {
+        let text = std::fs::read_to_string(DEFAULT_BINDINGS).unwrap();
+        let bindings = Bindings::parse(&text, DEFAULT_BINDINGS).unwrap();
+        let defaults = Bindings::new();
+        for &(action, name, _, _) in ACTIONS {
+            assert_eq!(keys(&bindings, action), keys(&defaults, action), "{}", name);
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_action() {
+        let err = Bindings::parse("[teclas]\nvolar = \"F\"", "prueba.toml").err().unwrap();
+        assert_eq!(err, "prueba.toml: acción desconocida volar");
+    }
+
+    #[test]
+    fn rejects_unknown_key() {
+        let text = "[teclas]\npausa = [\"Space\", \"Tecla\"]";
+        let err = Bindings::parse(text, "prueba.toml").err().unwrap();
+        assert_eq!(err, "prueba.toml: pausa: tecla desconocida Tecla");
+    }
+
+    #[test]
+    fn rejects_unknown_sections() {
+        let err = Bindings::parse("[raton]\nsensibilidad = 2", "prueba.toml").err().unwrap();
+        assert!(err.starts_with("Controles inválidos prueba.toml"), "{}", err);
+    }
+}
@@ -0,0 +1,155 @@
+/// Día juliano de la época J2000 (2000-01-01 12:00 TT, que aquí se trata como UTC)
+pub const J2000: f64 = 2_451_545.0;
+/// Día juliano de 1970-01-01 00:00 UTC
+const UNIX_EPOCH_JD: f64 = 2_440_587.5;
+
+/// Días desde 1970-01-01 -> (año, mes, día) del calendario gregoriano
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    (y, m, d)
+}
+
+/// (año, mes, día) del calendario gregoriano -> días desde 1970-01-01
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Fecha UTC `AAAA-MM-DD` o `AAAA-MM-DDTHH:MM[:SS]` -> día juliano
+pub fn parse_date(text: &str) -> Result<f64, String> {
+    let invalid = || {
+        format!(
+            "Fecha inválida: {} (ej. 2024-03-20 o 2024-03-20T15:30)",
+            text
+        )
+    };
+
+    let (date, time) = text.split_once(['T', ' ']).unwrap_or((text, "00:00"));
+
+    // El año puede ser negativo (calendario gregoriano proléptico)
+    let (sign, date) = match date.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, date),
+    };
+    let mut parts = date.split('-');
+    let (Some(y), Some(m), Some(d), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    let year = sign * y.parse::<i64>().map_err(|_| invalid())?;
+    let month: u32 = m.parse().map_err(|_| invalid())?;
+    let day: u32 = d.parse().map_err(|_| invalid())?;
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return Err(invalid());
+    }
+
+    let fields = time
+        .split(':')
+        .map(|f| f.parse::<u32>().map_err(|_| invalid()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (hour, minute, second) = match fields[..] {
+        [h, m] => (h, m, 0),
+        [h, m, s] => (h, m, s),
+        _ => return Err(invalid()),
+    };
+    if hour > 23 || minute > 59 || second > 59 {
+        return Err(invalid());
+    }
+
+    let seconds = (hour * 3600 + minute * 60 + second) as f64;
+    Ok(UNIX_EPOCH_JD + days_from_civil(year, month, day) as f64 + seconds / 86_400.0)
+}
+
+/// Día juliano -> `AAAA-MM-DD HH:MM:SS UTC`, redondeado al segundo
+pub fn format_date(jd: f64) -> String {
+    let seconds = ((jd - UNIX_EPOCH_JD) * 86_400.0).round() as i64;
+    let (y, m, d) = civil_from_days(seconds.div_euclid(86_400));
+    let rem = seconds.rem_euclid(86_400);
+    // Cuatro cifras también antes del año 0: -0044, no -044
+    let sign = if y < 0 { "-" } else { "" };
+    format!(
+        "{}{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        sign,
+        y.abs(),
+        m,
+        d,
+        rem / 3600,
+        (rem / 60) % 60,
+        rem % 60
+    )
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    let next = if month == 12 {
+        days_from_civil(year + 1, 1, 1)
+    } else {
+        days_from_civil(year, month + 1, 1)
+    };
+    (next - days_from_civil(year, month, 1)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_julian_days() {
+        assert_eq!(parse_date("2000-01-01T12:00"), Ok(J2000));
+        assert_eq!(parse_date("1970-01-01"), Ok(UNIX_EPOCH_JD));
+        // Primer día del calendario gregoriano y origen de los días julianos
+        // (proléptico: 1 de enero de 4713 a. C. juliano)
+        assert_eq!(parse_date("1582-10-15"), Ok(2_299_160.5));
+        assert_eq!(parse_date("-4713-11-24 12:00"), Ok(0.0));
+    }
+
+    #[test]
+    fn format_then_parse_round_trips() {
+        for text in [
+            "2000-01-01 12:00:00",
+            "2024-02-29 23:59:59",
+            "1582-10-04 06:30:00",
+            "1000-03-01 00:00:00",
+            "0000-02-29 18:00:00",
+            "-0044-03-15 09:15:30",
+        ] {
+            let jd = parse_date(text).unwrap();
+            assert_eq!(format_date(jd), format!("{} UTC", text));
+        }
+
+        for jd in [J2000, 0.0, 1_000_000.25, 2_299_160.5, 2_460_390.125] {
+            let text = format_date(jd);
+            let back = parse_date(text.trim_end_matches(" UTC")).unwrap();
+            assert!((back - jd).abs() < 1.0 / 86_400.0, "{} -> {} -> {}", jd, text, back);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        for text in [
+            "2024-02-30",
+            "2023-02-29",
+            "1900-02-29",
+            "2024-13-01",
+            "2024-03-DD",
+            "2024-03-20T24:00",
+            "2024-03-20T12",
+            "20240320",
+        ] {
+            assert!(parse_date(text).is_err(), "{}", text);
+        }
+    }
+}
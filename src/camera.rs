@@ -1,61 +1,136 @@
-use std::f32::consts::PI;
-
 use crate::input::InputState;
-use crate::math::Vec3;
+use crate::math::{Quat, Vec3};
+use crate::ship::Ship;
+
+/// FOV vertical por defecto (grados)
+pub const DEFAULT_FOV: f32 = 60.0;
+/// Límites del zoom: 1° es un telescopio, 90° un gran angular
+const MIN_FOV: f32 = 1.0;
+const MAX_FOV: f32 = 90.0;
+/// Cada paso de la rueda cambia el FOV en este factor
+const ZOOM_STEP: f32 = 1.15;
 
+/// Cámara de seis grados de libertad. La orientación es un cuaternión: en
+/// espacio local la cámara mira hacia -Z, con +X a la derecha y +Y arriba.
 pub struct Camera {
     pub position: Vec3,
-    pub yaw: f32,
-    pub pitch: f32,
+    pub orientation: Quat,
     pub fov_y: f32,
+    /// Velocidad en modo nave (unidades/s); en vuelo normal queda en cero
+    pub velocity: Vec3,
+    /// Modelo de vuelo inercial; `None` es el vuelo directo de siempre
+    pub ship: Option<Ship>,
 }
 
 impl Camera {
     pub fn new() -> Self {
         Camera {
-            position: Vec3::new(0.0, 30.0, 80.0),  
-            yaw: 0.0,  
-            pitch: -0.3,  
-            fov_y: 60.0_f32.to_radians(),
+            position: Vec3::new(0.0, 30.0, 80.0),
+            // Inclinada un poco hacia abajo, mirando al Sol
+            orientation: Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), -0.3),
+            fov_y: DEFAULT_FOV.to_radians(),
+            velocity: Vec3::zero(),
+            ship: None,
         }
     }
 
     pub fn forward(&self) -> Vec3 {
-        let cp = self.pitch.cos();
-        let sp = self.pitch.sin();
-        let cy = self.yaw.cos();
-        let sy = self.yaw.sin();
+        self.orientation.rotate(Vec3::new(0.0, 0.0, -1.0))
+    }
 
-        Vec3::new(sy * cp, sp, -cy * cp).normalized()
+    pub fn right(&self) -> Vec3 {
+        self.orientation.rotate(Vec3::new(1.0, 0.0, 0.0))
+    }
+
+    pub fn up(&self) -> Vec3 {
+        self.orientation.rotate(Vec3::up())
+    }
+
+    /// Orienta la cámara para mirar hacia `target`, manteniendo el "arriba" del mundo
+    pub fn look_at(&mut self, target: Vec3) {
+        let dir = target - self.position;
+        if dir.length() > 0.0 {
+            self.orientation = Quat::look_rotation(dir, Vec3::up());
+        }
+    }
+
+    /// Gira la cámara sobre uno de sus ejes locales
+    pub fn rotate_local(&mut self, axis: Vec3, angle: f32) {
+        self.orientation = (self.orientation * Quat::from_axis_angle(axis, angle)).normalized();
+    }
+
+    /// Acerca (`steps` > 0) o aleja el zoom cambiando el FOV dentro de sus límites
+    pub fn zoom(&mut self, steps: i32) {
+        let fov = self.fov_y.to_degrees() / ZOOM_STEP.powi(steps);
+        self.fov_y = fov.clamp(MIN_FOV, MAX_FOV).to_radians();
+    }
+
+    /// Cuánto más cerrado está el FOV que el normal; los giros se frenan en la
+    /// misma proporción para poder apuntar con el telescopio.
+    pub fn zoom_factor(&self) -> f32 {
+        self.fov_y / DEFAULT_FOV.to_radians()
+    }
+
+    /// Entra o sale del modo nave; siempre arranca quieta
+    pub fn toggle_ship(&mut self) {
+        self.ship = match self.ship {
+            Some(_) => None,
+            None => Some(Ship::new()),
+        };
+        self.velocity = Vec3::zero();
+    }
+
+    /// Base ortonormal de la cámara: (adelante, derecha, arriba).
+    /// `forward` es la dirección en la que mira la cámara.
+    pub fn basis(&self) -> (Vec3, Vec3, Vec3) {
+        (self.forward(), self.right(), self.up())
     }
 
     pub fn update(&mut self, dt: f32, input: &InputState) {
         let move_speed = 50.0;
-        let rot_speed = 1.5;
+        let rot_speed = 1.5 * self.zoom_factor();
+        let roll_speed = 1.5;
+
+        // Giros sobre los ejes locales: sin límites ni bloqueo en los polos
+        let yaw_axis = Vec3::up();
+        let pitch_axis = Vec3::new(1.0, 0.0, 0.0);
+        let roll_axis = Vec3::new(0.0, 0.0, 1.0);
 
         if input.mirar_izquierda {
-            self.yaw += rot_speed * dt;
+            self.rotate_local(yaw_axis, rot_speed * dt);
         }
         if input.mirar_derecha {
-            self.yaw -= rot_speed * dt;
+            self.rotate_local(yaw_axis, -rot_speed * dt);
         }
         if input.mirar_arriba {
-            self.pitch += rot_speed * dt;
+            self.rotate_local(pitch_axis, rot_speed * dt);
         }
         if input.mirar_abajo {
-            self.pitch -= rot_speed * dt;
+            self.rotate_local(pitch_axis, -rot_speed * dt);
         }
-
-        let max_pitch = 1.3;
-        if self.pitch > max_pitch {
-            self.pitch = max_pitch;
+        let (yaw, pitch) = input.mirar_raton;
+        let zoom = self.zoom_factor();
+        if yaw != 0.0 {
+            self.rotate_local(yaw_axis, yaw * zoom);
+        }
+        if pitch != 0.0 {
+            self.rotate_local(pitch_axis, pitch * zoom);
+        }
+        if input.girar_izquierda {
+            self.rotate_local(roll_axis, roll_speed * dt);
         }
-        if self.pitch < -max_pitch {
-            self.pitch = -max_pitch;
+        if input.girar_derecha {
+            self.rotate_local(roll_axis, -roll_speed * dt);
         }
 
-        let forward = self.forward();
-        let right = forward.cross(Vec3::up()).normalized();
+        let basis = self.basis();
+        if let Some(ship) = &mut self.ship {
+            ship.update(dt, input, basis, &mut self.velocity);
+            self.position = self.position + self.velocity * dt;
+            return;
+        }
+
+        let (forward, right, up) = basis;
         let mut velocity = Vec3::zero();
 
         if input.mover_adelante {
@@ -71,10 +146,10 @@ impl Camera {
             velocity = velocity - right;
         }
         if input.mover_arriba {
-            velocity.y += 1.0;
+            velocity = velocity + up;
         }
         if input.mover_abajo {
-            velocity.y -= 1.0;
+            velocity = velocity - up;
         }
 
         if velocity.length() > 0.0 {
@@ -82,4 +157,4 @@ impl Camera {
             self.position = self.position + velocity;
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,91 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::calendar::civil_from_days;
+use crate::renderer::Renderer;
+
+const CAPTURE_DIR: &str = "capturas";
+
+/// Paso de tiempo fijo por frame mientras se graba (30 fps de video),
+/// independiente de lo que tarde cada frame en generarse.
+pub const RECORD_DT: f32 = 1.0 / 30.0;
+
+/// Capturas de pantalla y grabación de secuencias de imágenes numeradas.
+pub struct Recorder {
+    sequence_dir: Option<PathBuf>,
+    frame: u32,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self {
+            sequence_dir: None,
+            frame: 0,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.sequence_dir.is_some()
+    }
+
+    /// Guarda el frame actual en `capturas/captura_<fecha>.png`
+    pub fn screenshot(&self, renderer: &Renderer) -> Result<PathBuf, String> {
+        let dir = PathBuf::from(CAPTURE_DIR);
+        create_dir(&dir)?;
+
+        let path = dir.join(format!("captura_{}.png", timestamp()));
+        renderer.save_png(&path.to_string_lossy())?;
+        Ok(path)
+    }
+
+    /// Empieza o termina una grabación en `capturas/grabacion_<fecha>/`.
+    /// Devuelve la carpeta de la grabación que empieza o termina.
+    pub fn toggle_recording(&mut self) -> Result<PathBuf, String> {
+        if let Some(dir) = self.sequence_dir.take() {
+            return Ok(dir);
+        }
+
+        let dir = PathBuf::from(CAPTURE_DIR).join(format!("grabacion_{}", timestamp()));
+        create_dir(&dir)?;
+        self.sequence_dir = Some(dir.clone());
+        self.frame = 0;
+        Ok(dir)
+    }
+
+    /// Si se está grabando, guarda el frame como `frame_00000.png`, `frame_00001.png`, ...
+    pub fn record_frame(&mut self, renderer: &Renderer) -> Result<(), String> {
+        let Some(dir) = &self.sequence_dir else {
+            return Ok(());
+        };
+
+        let path = dir.join(format!("frame_{:05}.png", self.frame));
+        renderer.save_png(&path.to_string_lossy())?;
+        self.frame += 1;
+        Ok(())
+    }
+}
+
+fn create_dir(dir: &PathBuf) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("No se pudo crear {}: {}", dir.display(), e))
+}
+
+/// Fecha y hora UTC actual como `AAAAMMDD_HHMMSS_mmm`
+fn timestamp() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let secs = now.as_secs();
+    let (y, m, d) = civil_from_days((secs / 86_400) as i64);
+    let rem = secs % 86_400;
+
+    format!(
+        "{:04}{:02}{:02}_{:02}{:02}{:02}_{:03}",
+        y,
+        m,
+        d,
+        rem / 3600,
+        (rem / 60) % 60,
+        rem % 60,
+        now.subsec_millis()
+    )
+}
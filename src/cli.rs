@@ -0,0 +1,132 @@
+use crate::calendar;
+use crate::world::{Integrator, Scale};
+
+pub const DEFAULT_SCENE: &str = "scenes/demo.toml";
+
+pub const USAGE: &str = "\
+Uso: Renderizador_Sistema_Solar [escena.toml] [opciones]
+
+Opciones:
+  --size <ancho>x<alto>   Tamaño de la imagen/ventana (por defecto 800x600)
+  --headless              Renderiza sin ventana y guarda un PNG
+  --time <segundos>       Tiempo de simulación a avanzar antes de renderizar (headless)
+  --output <archivo.png>  Archivo de salida (headless, por defecto render.png)
+  --mouse-sensitivity <g> Grados de giro por píxel con el mouse capturado (por defecto 0.15)
+  --invert-mouse          Invierte el eje vertical del mouse
+  --nbody                 Arranca con gravedad mutua entre los cuerpos (modo N-cuerpos)
+  --integrator <nombre>   Integrador del modo N-cuerpos: leapfrog (por defecto) o rk4
+  --keys <controles.toml> Archivo de controles (por defecto controles.toml si existe)
+  --scale <modo>          Escala de las escenas en unidades reales: compressed (por defecto) o true
+  --date <fecha>          Fecha UTC inicial (AAAA-MM-DD o AAAA-MM-DDTHH:MM)
+  --fps <n>               Tope de cuadros por segundo de la ventana (por defecto 60, 0 = sin tope)";
+
+/// Opciones de línea de comandos.
+pub struct Options {
+    pub scene: String,
+    pub width: usize,
+    pub height: usize,
+    pub headless: bool,
+    pub time: f64,
+    pub output: String,
+    pub mouse_sensitivity: f32,
+    pub invert_mouse: bool,
+    pub keys: Option<String>,
+    pub nbody: bool,
+    pub integrator: Integrator,
+    pub scale: Scale,
+    /// Día juliano inicial
+    pub date: Option<f64>,
+    /// Tope de FPS de la ventana (0 = sin tope)
+    pub fps: u32,
+}
+
+impl Options {
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+        let mut opts = Options {
+            scene: DEFAULT_SCENE.to_string(),
+            width: 800,
+            height: 600,
+            headless: false,
+            time: 0.0,
+            output: "render.png".to_string(),
+            mouse_sensitivity: 0.15,
+            invert_mouse: false,
+            keys: None,
+            nbody: false,
+            integrator: Integrator::Leapfrog,
+            scale: Scale::Compressed,
+            date: None,
+            fps: 60,
+        };
+        let mut scene_set = false;
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| format!("Falta el valor de {}", name))
+            };
+
+            match arg.as_str() {
+                "--headless" => opts.headless = true,
+                "--time" => {
+                    let v = value("--time")?;
+                    opts.time = v.parse().map_err(|_| format!("--time inválido: {}", v))?;
+                }
+                "--output" => opts.output = value("--output")?,
+                "--mouse-sensitivity" => {
+                    let v = value("--mouse-sensitivity")?;
+                    opts.mouse_sensitivity = v
+                        .parse()
+                        .ok()
+                        .filter(|&s: &f32| s > 0.0)
+                        .ok_or_else(|| format!("--mouse-sensitivity inválido: {}", v))?;
+                }
+                "--invert-mouse" => opts.invert_mouse = true,
+                "--keys" => opts.keys = Some(value("--keys")?),
+                "--nbody" => opts.nbody = true,
+                "--integrator" => {
+                    let v = value("--integrator")?;
+                    opts.integrator = match v.as_str() {
+                        "leapfrog" => Integrator::Leapfrog,
+                        "rk4" => Integrator::Rk4,
+                        _ => return Err(format!("--integrator inválido: {} (leapfrog o rk4)", v)),
+                    };
+                }
+                "--scale" => {
+                    let v = value("--scale")?;
+                    opts.scale = match v.as_str() {
+                        "true" => Scale::True,
+                        "compressed" => Scale::Compressed,
+                        _ => return Err(format!("--scale inválido: {} (true o compressed)", v)),
+                    };
+                }
+                "--date" => opts.date = Some(calendar::parse_date(&value("--date")?)?),
+                "--fps" => {
+                    let v = value("--fps")?;
+                    opts.fps = v.parse().map_err(|_| format!("--fps inválido: {}", v))?;
+                }
+                "--size" => {
+                    let v = value("--size")?;
+                    let (w, h) = v
+                        .split_once('x')
+                        .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+                        .filter(|&(w, h)| w > 0 && h > 0)
+                        .ok_or_else(|| format!("--size inválido: {} (ej. 1280x720)", v))?;
+                    opts.width = w;
+                    opts.height = h;
+                }
+                "-h" | "--help" => return Err(USAGE.to_string()),
+                _ if arg.starts_with("--") => {
+                    return Err(format!("Opción desconocida: {}\n\n{}", arg, USAGE));
+                }
+                _ if !scene_set => {
+                    opts.scene = arg;
+                    scene_set = true;
+                }
+                _ => return Err(format!("Argumento inesperado: {}\n\n{}", arg, USAGE)),
+            }
+        }
+
+        Ok(opts)
+    }
+}
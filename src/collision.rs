@@ -1,29 +1,247 @@
 use crate::camera::Camera;
-use crate::math::Vec3;
-use crate::world::{BodyKind, SolarSystem};
-
-pub fn resolve_collisions(system: &SolarSystem, camera: &mut Camera) {
-    let margin = 2.0;
-
-    for i in 0..system.bodies.len() {
-        let b = &system.bodies[i];
-
-        match b.kind {
-            BodyKind::Star | BodyKind::Planet | BodyKind::Moon => {
-                let center = system.body_position(i);
-                let to_cam = camera.position - center;
-                let dist = to_cam.length();
-                let min_dist = b.radius + margin;
-
-                if dist < min_dist {
-                    let dir = if dist == 0.0 {
-                        Vec3::up()
-                    } else {
-                        to_cam / dist
-                    };
-                    camera.position = center + dir * min_dist;
-                }
-            }
+use crate::math::{Vec3, ray_sphere};
+use crate::world::SolarSystem;
+
+/// Distancia que la cámara mantiene con la superficie de los cuerpos
+pub const MARGIN: f32 = 2.0;
+/// Giro máximo (radianes) de un cuerpo en un paso para tomar su movimiento
+/// como una recta; con escalas de tiempo altas la cuerda corta la órbita por
+/// dentro y chocaría con cámaras por las que el cuerpo nunca pasó
+const MAX_SWEEP_ANGLE: f32 = 0.05;
+
+/// Choque de la cámara con un cuerpo durante un paso de simulación
+pub struct Contact {
+    pub body: usize,
+    /// Normal de la superficie en el punto de contacto (hacia afuera del cuerpo)
+    pub normal: Vec3,
+    /// Fracción del paso (0..1) en la que la cámara tocó el cuerpo; los
+    /// solapamientos que no vienen del barrido cuentan al final del paso (1)
+    pub time: f32,
+}
+
+/// Posición de cada cuerpo, para guardarla antes de avanzar la simulación
+pub fn snapshot(system: &SolarSystem) -> Vec<Vec3> {
+    (0..system.bodies.len())
+        .map(|i| system.body_position(i))
+        .collect()
+}
+
+/// Prueba continua: la cámara va de `from` a `to` mientras cada cuerpo va de
+/// `bodies_before[i]` a su posición actual en `sim_dt` segundos simulados. En
+/// el sistema del cuerpo eso es un segmento contra una esfera quieta de radio
+/// `radius + MARGIN`, así que no se atraviesa nada aunque el paso sea enorme.
+/// Los cuerpos que giran demasiado en el paso (ver `MAX_SWEEP_ANGLE`) quedan
+/// fuera del barrido. Devuelve el primer contacto.
+pub fn sweep(
+    system: &SolarSystem,
+    bodies_before: &[Vec3],
+    sim_dt: f32,
+    from: Vec3,
+    to: Vec3,
+) -> Option<Contact> {
+    let mut first: Option<Contact> = None;
+
+    for (i, body) in system.bodies.iter().enumerate() {
+        if orbital_travel(system, i, sim_dt) > MAX_SWEEP_ANGLE {
+            continue;
         }
+        let min_dist = body.radius + MARGIN;
+        let rel_from = from - bodies_before[i];
+        let rel_to = to - system.body_position(i);
+
+        // Si ya empezaba dentro se encarga el empuje de `resolve_collisions`
+        if rel_from.length() < min_dist {
+            continue;
+        }
+
+        let delta = rel_to - rel_from;
+        let len = delta.length();
+        if len == 0.0 {
+            continue;
+        }
+        let Some(t) = ray_sphere(rel_from, delta / len, Vec3::zero(), min_dist) else {
+            continue;
+        };
+        if t > len {
+            continue;
+        }
+
+        let time = t / len;
+        if first.as_ref().is_none_or(|c| time < c.time) {
+            first = Some(Contact {
+                body: i,
+                normal: (rel_from + delta * time).normalized(),
+                time,
+            });
+        }
+    }
+
+    first
+}
+
+/// Ángulo (radianes) que recorre el cuerpo en `sim_dt` sumando su órbita y
+/// las de sus padres, que también lo arrastran
+fn orbital_travel(system: &SolarSystem, index: usize, sim_dt: f32) -> f32 {
+    let mut travel = 0.0;
+    let mut current = Some(index);
+    while let Some(i) = current {
+        let body = &system.bodies[i];
+        if let Some(orbit) = &body.orbit {
+            travel += (orbit.mean_motion * sim_dt).abs();
+        }
+        current = body.parent;
+    }
+    travel
+}
+
+/// Detiene la cámara en la superficie del primer cuerpo que toque al ir de
+/// `from` a su posición actual, y saca a la cámara de cualquier cuerpo en el
+/// que haya quedado metida. En ambos casos se anula la componente de su
+/// velocidad (modo nave) que apunta hacia el cuerpo.
+pub fn resolve_collisions(
+    system: &SolarSystem,
+    bodies_before: &[Vec3],
+    sim_dt: f32,
+    from: Vec3,
+    camera: &mut Camera,
+) -> Option<Contact> {
+    let mut contact = sweep(system, bodies_before, sim_dt, from, camera.position);
+    if let Some(c) = &contact {
+        let center = system.body_position(c.body);
+        push_out(
+            camera,
+            center,
+            c.normal,
+            system.bodies[c.body].radius + MARGIN,
+        );
+    }
+
+    for (i, body) in system.bodies.iter().enumerate() {
+        let center = system.body_position(i);
+        let to_cam = camera.position - center;
+        let dist = to_cam.length();
+        let min_dist = body.radius + MARGIN;
+
+        if dist < min_dist {
+            let normal = if dist == 0.0 {
+                Vec3::up()
+            } else {
+                to_cam / dist
+            };
+            push_out(camera, center, normal, min_dist);
+            contact.get_or_insert(Contact {
+                body: i,
+                normal,
+                time: 1.0,
+            });
+        }
+    }
+
+    contact
+}
+
+fn push_out(camera: &mut Camera, center: Vec3, normal: Vec3, min_dist: f32) {
+    camera.position = center + normal * min_dist;
+
+    let into = camera.velocity.dot(normal);
+    if into < 0.0 {
+        camera.velocity = camera.velocity - normal * into;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{Body, BodyKind, FreeMotion, Orbit};
+
+    fn body(radius: f32, orbit: Option<Orbit>, free: Option<FreeMotion>) -> Body {
+        Body {
+            name: "Prueba".to_string(),
+            kind: BodyKind::Planet,
+            radius,
+            mass: 0.0,
+            color: 0xFFFFFFFF,
+            orbit,
+            parent: None,
+            free,
+            rotation_period: 0.0,
+            rotation_phase: 0.0,
+            axial_tilt: 0.0,
+            tidally_locked: false,
+            texture: None,
+            night_texture: None,
+        }
+    }
+
+    fn close(a: Vec3, b: Vec3) -> bool {
+        (a - b).length() < 1e-4
+    }
+
+    #[test]
+    fn fast_camera_does_not_tunnel() {
+        // Esfera de radio 3 (5 con el margen) en el origen, cruzada en un solo paso
+        let system = SolarSystem::new(vec![body(3.0, None, None)]);
+        let before = snapshot(&system);
+        let from = Vec3::new(-100.0, 0.0, 0.0);
+        let to = Vec3::new(100.0, 0.0, 0.0);
+
+        let contact = sweep(&system, &before, 1.0, from, to).unwrap();
+        assert_eq!(contact.body, 0);
+        assert!((contact.time - 95.0 / 200.0).abs() < 1e-5);
+        assert!(close(contact.normal, Vec3::new(-1.0, 0.0, 0.0)));
+
+        let mut camera = Camera::new();
+        camera.position = to;
+        resolve_collisions(&system, &before, 1.0, from, &mut camera).unwrap();
+        assert!(close(camera.position, Vec3::new(-5.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn moving_body_hits_a_still_camera() {
+        let free = FreeMotion {
+            position: Vec3::new(-50.0, 0.0, 0.0),
+            velocity: Vec3::new(100.0, 0.0, 0.0),
+        };
+        let mut system = SolarSystem::new(vec![body(3.0, None, Some(free))]);
+        let before = snapshot(&system);
+        system.time = 1.0;
+
+        let camera = Vec3::new(0.0, 1.0, 0.0);
+        let contact = sweep(&system, &before, 1.0, camera, camera).unwrap();
+        // En el sistema del cuerpo la cámara va de x = 50 a x = -50
+        let x = 24.0f32.sqrt();
+        assert!((contact.time - (50.0 - x) / 100.0).abs() < 1e-5);
+        assert!(close(contact.normal, Vec3::new(x, 1.0, 0.0) / 5.0));
+    }
+
+    #[test]
+    fn fast_orbits_are_not_swept_as_chords() {
+        // Media vuelta en un paso: la cuerda pasa por el origen, el cuerpo no
+        let orbit = Orbit {
+            semi_major_axis: 10.0,
+            eccentricity: 0.0,
+            inclination: 0.0,
+            ascending_node: 0.0,
+            periapsis: 0.0,
+            mean_anomaly: 0.0,
+            mean_motion: std::f32::consts::PI,
+            node_rate: 0.0,
+            periapsis_rate: 0.0,
+        };
+        let mut system = SolarSystem::new(vec![body(1.0, Some(orbit), None)]);
+        let before = snapshot(&system);
+        system.time = 1.0;
+
+        let mut camera = Camera::new();
+        camera.position = Vec3::zero();
+        assert!(resolve_collisions(&system, &before, 1.0, camera.position, &mut camera).is_none());
+        assert!(close(camera.position, Vec3::zero()));
+
+        // Con un paso corto el mismo cuerpo sí se barre
+        let before = snapshot(&system);
+        system.time += 0.01;
+        let start = system.body_position(0) + Vec3::new(0.0, 0.0, 20.0);
+        let end = system.body_position(0) - Vec3::new(0.0, 0.0, 20.0);
+        assert!(sweep(&system, &before, 0.01, start, end).is_some());
     }
 }
@@ -0,0 +1,132 @@
+use crate::calendar;
+use crate::camera::Camera;
+use crate::cli::Options;
+use crate::math::Vec3;
+use crate::renderer::Renderer;
+use crate::view;
+use crate::world::Scene;
+
+/// Renderiza un único frame sin ventana: avanza la simulación `opts.time`
+/// segundos y guarda el framebuffer como PNG en `opts.output`.
+pub fn run(scene: Scene, opts: &Options) -> Result<(), String> {
+    let Scene {
+        mut system,
+        skybox,
+        camera: start,
+    } = scene;
+
+    let mut renderer = Renderer::new(opts.width, opts.height);
+    let camera = start_camera(start);
+
+    if opts.nbody {
+        system.start_nbody(opts.integrator);
+    }
+    // Con gravedad mutua, en tramos que el integrador cubra sin agotar sus subpasos
+    let chunk = system
+        .nbody
+        .as_ref()
+        .map_or(f64::INFINITY, |n| n.max_interval() as f64);
+    let mut remaining = opts.time;
+    while remaining.abs() > 0.0 {
+        let step = remaining.clamp(-chunk, chunk);
+        system.update(step as f32);
+        remaining -= step;
+    }
+
+    if opts.nbody {
+        let drift = system.drift();
+        println!(
+            "Deriva tras {:.1} s ({}): energía {:.2e}, momento angular {:.2e}",
+            opts.time,
+            opts.integrator.name(),
+            drift.energy,
+            drift.angular_momentum
+        );
+    }
+
+    println!("Fecha: {}", calendar::format_date(system.date()));
+
+    view::draw_scene(&mut renderer, &system, &camera, skybox.as_ref());
+
+    renderer.save_png(&opts.output)?;
+    println!("Imagen guardada en {}", opts.output);
+    Ok(())
+}
+
+/// Cámara por defecto, o en la posición de la escena mirando al origen
+fn start_camera(start: Option<Vec3>) -> Camera {
+    let mut camera = Camera::new();
+    if let Some(position) = start {
+        camera.position = position;
+        camera.look_at(Vec3::zero());
+    }
+    camera
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::Scale;
+
+    /// Un frame con contenido: el Sol brillante en su sitio y cientos de colores
+    fn assert_drawn(pixels: &[u32], sun: usize) {
+        let red = (pixels[sun] >> 16) & 0xFF;
+        assert!(red > 150, "el centro del Sol es {:08X}", pixels[sun]);
+
+        let mut colors = pixels.to_vec();
+        colors.sort_unstable();
+        colors.dedup();
+        assert!(colors.len() > 100, "solo {} colores", colors.len());
+    }
+
+    #[test]
+    fn renders_demo_frames() {
+        let Scene {
+            mut system,
+            skybox,
+            camera: start,
+        } = Scene::load("scenes/demo.toml", Scale::Compressed).unwrap();
+        let mut renderer = Renderer::new(200, 150);
+        let camera = start_camera(start);
+
+        for _ in 0..3 {
+            view::draw_scene(&mut renderer, &system, &camera, skybox.as_ref());
+            let ((x, y), _) = system.project_body(0, &renderer, &camera).unwrap();
+            assert_drawn(renderer.buffer(), y as usize * renderer.width + x as usize);
+            system.update(0.5);
+        }
+    }
+
+    #[test]
+    fn run_saves_the_image() {
+        let output = std::env::temp_dir().join("renderizador_headless_test.png");
+        let args = [
+            "scenes/demo.toml",
+            "--headless",
+            "--size",
+            "200x150",
+            "--time",
+            "2",
+        ];
+        let mut args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        args.extend(["--output".to_string(), output.display().to_string()]);
+        let opts = Options::parse(args.into_iter()).unwrap();
+
+        let scene = Scene::load(&opts.scene, opts.scale).unwrap();
+        let camera = start_camera(scene.camera);
+        let renderer = Renderer::new(opts.width, opts.height);
+        let ((x, y), _) = scene.system.project_body(0, &renderer, &camera).unwrap();
+
+        run(scene, &opts).unwrap();
+        let image = image::open(&output).unwrap().to_rgb8();
+        std::fs::remove_file(&output).unwrap();
+
+        assert_eq!(image.dimensions(), (200, 150));
+        let pixels: Vec<u32> = image
+            .pixels()
+            .map(|p| u32::from_be_bytes([0xFF, p[0], p[1], p[2]]))
+            .collect();
+        // El Sol no se mueve: está donde se proyecta al arrancar
+        assert_drawn(&pixels, y as usize * 200 + x as usize);
+    }
+}
@@ -0,0 +1,229 @@
+use crate::calendar;
+use crate::camera::Camera;
+use crate::math::Vec3;
+use crate::renderer::Renderer;
+use crate::renderer::font;
+use crate::sim_clock::SimClock;
+use crate::world::SolarSystem;
+
+const TEXT_SCALE: i32 = 2;
+const LINE_HEIGHT: i32 = (font::GLYPH_H + 3) * TEXT_SCALE;
+const PADDING: i32 = 6;
+const MARGIN: i32 = 8;
+
+const TEXT_COLOR: u32 = 0xFFE6ECFF;
+const SELECTION_COLOR: u32 = 0xFFFFD040;
+const PANEL_COLOR: u32 = 0xA0000000;
+
+/// Estado de la app que muestra el HUD en un frame
+pub struct HudContext<'a> {
+    pub camera: &'a Camera,
+    pub system: &'a SolarSystem,
+    pub clock: &'a SimClock,
+    pub recording: bool,
+    pub selected: Option<usize>,
+    /// Cuerpo que sigue la cámara en modo órbita
+    pub orbit_target: Option<usize>,
+    /// Mira en el centro de la pantalla (mouse capturado)
+    pub crosshair: bool,
+    /// Diálogo de "ir a fecha" abierto, con lo escrito hasta ahora
+    pub date_prompt: Option<String>,
+}
+
+/// Capa de información en pantalla: cámara, tiempo, FPS y cuerpo más cercano.
+/// Se dibuja encima de la escena, después de los cuerpos.
+pub struct Hud {
+    pub visible: bool,
+    /// Panel de diagnóstico de la simulación (energía, momento, elementos)
+    pub diagnostics: bool,
+    fps: f32,
+    speed: f32,
+    last_camera: Option<Vec3>,
+}
+
+impl Hud {
+    pub fn new() -> Self {
+        Self {
+            visible: true,
+            diagnostics: false,
+            fps: 0.0,
+            speed: 0.0,
+            last_camera: None,
+        }
+    }
+
+    /// `frame_dt` es el tiempo real del frame (para los FPS) y `step_dt` el paso
+    /// con el que se movió la cámara (para la velocidad); difieren al grabar.
+    pub fn update(&mut self, frame_dt: f32, step_dt: f32, camera: &Camera) {
+        // Suavizado exponencial para que los números no parpadeen
+        let k = 0.1;
+        if frame_dt > 0.0 {
+            self.fps += (1.0 / frame_dt - self.fps) * k;
+        }
+        if let Some(last) = self.last_camera
+            && step_dt > 0.0
+        {
+            let speed = (camera.position - last).length() / step_dt;
+            self.speed += (speed - self.speed) * k;
+        }
+        self.last_camera = Some(camera.position);
+    }
+
+    pub fn draw(&self, renderer: &mut Renderer, ctx: &HudContext) {
+        // El diálogo de fecha se ve aunque el HUD esté oculto
+        if let Some(text) = &ctx.date_prompt {
+            let lines = [
+                format!("IR A FECHA (UTC): {}", text),
+                "ENTER CONFIRMA  ESC CANCELA".to_string(),
+            ];
+            let (w, h) = panel_size(&lines);
+            let x = (renderer.width as i32 - w) / 2;
+            let y = renderer.height as i32 - h - MARGIN;
+            draw_panel(renderer, x, y, &lines);
+        }
+
+        if !self.visible {
+            return;
+        }
+        let HudContext {
+            camera,
+            system,
+            clock,
+            recording,
+            selected,
+            orbit_target,
+            crosshair,
+            ..
+        } = *ctx;
+
+        // Anillo alrededor del cuerpo seleccionado
+        if let Some(i) = selected
+            && let Some((screen, radius_px)) = system.project_body(i, renderer, camera)
+        {
+            renderer.draw_circle(screen, radius_px + 4, SELECTION_COLOR);
+        }
+
+        if crosshair {
+            let (cx, cy) = (renderer.width as i32 / 2, renderer.height as i32 / 2);
+            renderer.draw_line((cx - 6, cy), (cx + 6, cy), TEXT_COLOR);
+            renderer.draw_line((cx, cy - 6), (cx, cy + 6), TEXT_COLOR);
+        }
+
+        let p = camera.position;
+        let mut tiempo = format!("TIEMPO x{}", clock.scale());
+        if clock.paused {
+            tiempo.push_str("  PAUSA");
+        }
+        if recording {
+            tiempo.push_str("  REC");
+        }
+        if let Some(nbody) = &system.nbody {
+            tiempo.push_str(&format!("  GRAVEDAD {}", nbody.integrator.name()));
+        }
+
+        let mut lines = vec![
+            format!("POS {:.1} {:.1} {:.1}", p.x, p.y, p.z),
+            format!("VEL {:.1} u/s", self.speed),
+            tiempo,
+            format!("FECHA {}", calendar::format_date(system.date())),
+            format!("DJ {:.5}  T = {:.1} s", system.date(), system.time),
+            format!("FPS {:.0}", self.fps),
+            format!("FOV {:.1}", camera.fov_y.to_degrees()),
+        ];
+        if let Some(ship) = &camera.ship {
+            let asistencia = if ship.flight_assist { "  ASISTENCIA" } else { "" };
+            lines.push(format!(
+                "NAVE {:.1} u/s  POT {:.0}%{}",
+                camera.velocity.length(),
+                ship.throttle * 100.0,
+                asistencia
+            ));
+        }
+        lines.push(match orbit_target {
+            Some(i) => format!("CAMARA ORBITA {}", system.bodies[i].name),
+            None => "CAMARA LIBRE".to_string(),
+        });
+        if let Some((i, dist)) = system.nearest_body(camera.position) {
+            lines.push(format!(
+                "CERCA {} {:.1} u",
+                system.bodies[i].name,
+                dist.max(0.0)
+            ));
+        }
+        if let Some(i) = selected {
+            let dist =
+                (system.body_position(i) - camera.position).length() - system.bodies[i].radius;
+            lines.push(format!(
+                "SELECCION {} {:.1} u",
+                system.bodies[i].name,
+                dist.max(0.0)
+            ));
+        }
+
+        draw_panel(renderer, MARGIN, MARGIN, &lines);
+
+        if self.diagnostics {
+            let lines = diagnostics_lines(system, selected);
+            let (w, _) = panel_size(&lines);
+            let x = renderer.width as i32 - w - MARGIN;
+            draw_panel(renderer, x, MARGIN, &lines);
+        }
+    }
+}
+
+/// Energía, momento angular y su deriva; y los elementos osculantes del
+/// cuerpo seleccionado respecto a su padre
+fn diagnostics_lines(system: &SolarSystem, selected: Option<usize>) -> Vec<String> {
+    let d = &system.diagnostics;
+    let drift = system.drift();
+    let modo = if system.nbody.is_some() { "N-CUERPOS" } else { "KEPLER" };
+
+    let mut lines = vec![
+        format!("DIAGNOSTICO {}", modo),
+        format!("E {:.5e}", d.energy()),
+        format!("K {:.4e}  U {:.4e}", d.kinetic, d.potential),
+        format!("L {:.5e}", d.angular_momentum_norm()),
+        format!("DERIVA E {:.1e}  L {:.1e}", drift.energy, drift.angular_momentum),
+    ];
+
+    if let Some(i) = selected
+        && let Some(el) = system.orbital_elements(i)
+    {
+        let parent = system.bodies[i].parent.map_or("", |p| system.bodies[p].name.as_str());
+        lines.push(format!("{} / {}", system.bodies[i].name, parent));
+        lines.push(format!("A {:.2}  EXC {:.4}", el.semi_major_axis, el.eccentricity));
+        lines.push(format!(
+            "INC {:.2}  NODO {:.1}  PERI {:.1}",
+            el.inclination.to_degrees(),
+            el.ascending_node.to_degrees(),
+            el.periapsis.to_degrees()
+        ));
+        lines.push(match el.period {
+            Some(p) => format!("PERIODO {:.2} s", p),
+            None => "ORBITA ABIERTA".to_string(),
+        });
+    }
+    lines
+}
+
+/// Ancho y alto en píxeles del panel con estas líneas
+fn panel_size(lines: &[String]) -> (i32, i32) {
+    let width = lines
+        .iter()
+        .map(|l| font::text_width(l, TEXT_SCALE))
+        .max()
+        .unwrap_or(0);
+    let height = lines.len() as i32 * LINE_HEIGHT;
+    (width + 2 * PADDING, height + 2 * PADDING)
+}
+
+/// Panel semitransparente con una línea de texto por renglón
+fn draw_panel(renderer: &mut Renderer, x: i32, y: i32, lines: &[String]) {
+    let (width, height) = panel_size(lines);
+    renderer.fill_rect(x, y, width, height, PANEL_COLOR);
+
+    for (i, line) in lines.iter().enumerate() {
+        let ly = y + PADDING + i as i32 * LINE_HEIGHT;
+        renderer.draw_text(x + PADDING, ly, line, TEXT_COLOR, TEXT_SCALE);
+    }
+}
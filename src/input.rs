@@ -1,6 +1,16 @@
-use minifb::{Key, Window};
+use minifb::{Key, KeyRepeat, MouseButton, MouseMode, Window};
 
+use crate::bindings::{Action, Bindings};
+
+/// Plantilla del diálogo de fecha: los dígitos escritos ocupan las letras
+const PLANTILLA_FECHA: &str = "AAAA-MM-DD HH:MM";
+const DIGITOS_FECHA: usize = 12;
+
+/// Estado de los controles en el frame actual. Las teclas salen del mapa de
+/// acciones (`Bindings`); el mouse se lee directamente.
 pub struct InputState {
+    bindings: Bindings,
+
     pub mover_adelante: bool,
     pub mover_atras: bool,
     pub mover_izquierda: bool,
@@ -12,16 +22,71 @@ pub struct InputState {
     pub mirar_derecha: bool,
     pub mirar_arriba: bool,
     pub mirar_abajo: bool,
+    pub girar_izquierda: bool,
+    pub girar_derecha: bool,
+
+    // Modo nave: potencia del motor (mantener) y alternar modo/asistencia
+    pub subir_potencia: bool,
+    pub bajar_potencia: bool,
+    pub alternar_nave: bool,
+    pub alternar_asistencia: bool,
 
-    pub warp_1: bool,
-    pub warp_2: bool,
-    pub warp_3: bool,
+    /// Índice del cuerpo al que saltar con las teclas 0-9
+    pub warp_instantaneo: Option<usize>,
     pub warp_animated: bool,
+
+    // Control del tiempo (solo el frame en que se presiona la tecla)
+    pub pausa: bool,
+    pub acelerar: bool,
+    pub desacelerar: bool,
+    pub invertir_tiempo: bool,
+    pub paso_simple: bool,
+    pub ir_a_fecha: bool,
+
+    /// Dígitos del diálogo de fecha (`None` = cerrado)
+    pub fecha: Option<String>,
+    /// Fecha confirmada con Enter en este frame (`AAAA-MM-DD[ HH:MM]`)
+    pub fecha_confirmada: Option<String>,
+    /// El diálogo de fecha se quedó con el teclado en este frame: el resto de
+    /// las acciones no deben atenderse
+    pub escribiendo_fecha: bool,
+
+    pub captura: bool,
+    pub grabar: bool,
+
+    pub alternar_hud: bool,
+    pub alternar_etiquetas: bool,
+    pub alternar_orbita: bool,
+    pub alternar_gravedad: bool,
+    pub alternar_diagnostico: bool,
+    pub salir: bool,
+
+    /// Posición del clic izquierdo en este frame (solo al presionar)
+    pub clic: Option<(f32, f32)>,
+    boton_izquierdo_antes: bool,
+
+    /// Mouse capturado: sus movimientos giran la cámara (se alterna con M)
+    pub raton_capturado: bool,
+    pub alternar_raton: bool,
+    /// Radianes de giro por píxel de movimiento del mouse
+    pub sensibilidad_raton: f32,
+    /// Invierte el eje vertical del mouse (mover hacia arriba mira hacia abajo)
+    pub invertir_raton: bool,
+    /// Giro pedido por el mouse aún sin aplicar: (guiñada, cabeceo) en radianes
+    pub mirar_raton: (f32, f32),
+    raton_antes: Option<(f32, f32)>,
+
+    /// Pasos de la rueda en este frame: positivo acerca (menos FOV), negativo aleja
+    pub zoom: i32,
+    /// Clic central: vuelve al FOV por defecto
+    pub restablecer_zoom: bool,
+    boton_central_antes: bool,
 }
 
 impl InputState {
-    pub fn new() -> Self {
+    pub fn new(bindings: Bindings) -> Self {
         Self {
+            bindings,
             mover_adelante: false,
             mover_atras: false,
             mover_izquierda: false,
@@ -32,30 +97,258 @@ impl InputState {
             mirar_derecha: false,
             mirar_arriba: false,
             mirar_abajo: false,
-            warp_1: false,
-            warp_2: false,
-            warp_3: false,
+            girar_izquierda: false,
+            girar_derecha: false,
+            subir_potencia: false,
+            bajar_potencia: false,
+            alternar_nave: false,
+            alternar_asistencia: false,
+            warp_instantaneo: None,
             warp_animated: false,
+            pausa: false,
+            acelerar: false,
+            desacelerar: false,
+            invertir_tiempo: false,
+            paso_simple: false,
+            ir_a_fecha: false,
+            fecha: None,
+            fecha_confirmada: None,
+            escribiendo_fecha: false,
+            captura: false,
+            grabar: false,
+            alternar_hud: false,
+            alternar_etiquetas: false,
+            alternar_orbita: false,
+            alternar_gravedad: false,
+            alternar_diagnostico: false,
+            salir: false,
+            clic: None,
+            boton_izquierdo_antes: false,
+            raton_capturado: false,
+            alternar_raton: false,
+            sensibilidad_raton: 0.15_f32.to_radians(),
+            invertir_raton: false,
+            mirar_raton: (0.0, 0.0),
+            raton_antes: None,
+            zoom: 0,
+            restablecer_zoom: false,
+            boton_central_antes: false,
         }
     }
 
     pub fn update(&mut self, window: &Window) {
-        self.mover_adelante = window.is_key_down(Key::W);
-        self.mover_atras = window.is_key_down(Key::S);
-        self.mover_izquierda = window.is_key_down(Key::A);
-        self.mover_derecha = window.is_key_down(Key::D);
-        self.mover_arriba = window.is_key_down(Key::E);
-        self.mover_abajo = window.is_key_down(Key::Q);
-
-        self.mirar_izquierda = window.is_key_down(Key::Left);
-        self.mirar_derecha = window.is_key_down(Key::Right);
-        self.mirar_arriba = window.is_key_down(Key::Up);
-        self.mirar_abajo = window.is_key_down(Key::Down);
-
-        self.warp_1 = window.is_key_down(Key::Key1);
-        self.warp_2 = window.is_key_down(Key::Key2);
-        self.warp_3 = window.is_key_down(Key::Key3);
-
-        self.warp_animated = window.is_key_down(Key::Space);
+        let activa = |action| self.bindings.active(window, action);
+
+        self.mover_adelante = activa(Action::MoveForward);
+        self.mover_atras = activa(Action::MoveBack);
+        self.mover_izquierda = activa(Action::MoveLeft);
+        self.mover_derecha = activa(Action::MoveRight);
+        self.mover_arriba = activa(Action::MoveUp);
+        self.mover_abajo = activa(Action::MoveDown);
+
+        self.mirar_izquierda = activa(Action::LookLeft);
+        self.mirar_derecha = activa(Action::LookRight);
+        self.mirar_arriba = activa(Action::LookUp);
+        self.mirar_abajo = activa(Action::LookDown);
+        self.girar_izquierda = activa(Action::RollLeft);
+        self.girar_derecha = activa(Action::RollRight);
+        self.subir_potencia = activa(Action::ThrottleUp);
+        self.bajar_potencia = activa(Action::ThrottleDown);
+
+        self.warp_instantaneo = (0..10).find(|&i| activa(Action::Jump(i)));
+        self.warp_animated = activa(Action::WarpToSelection);
+
+        self.pausa = activa(Action::Pause);
+        self.acelerar = activa(Action::SpeedUp);
+        self.desacelerar = activa(Action::SlowDown);
+        self.invertir_tiempo = activa(Action::ReverseTime);
+        self.paso_simple = activa(Action::StepTime);
+        self.ir_a_fecha = activa(Action::JumpToDate);
+
+        self.captura = activa(Action::Screenshot);
+        self.grabar = activa(Action::Record);
+
+        self.alternar_hud = activa(Action::ToggleHud);
+        self.alternar_etiquetas = activa(Action::ToggleLabels);
+        self.alternar_orbita = activa(Action::ToggleOrbit);
+        self.alternar_nave = activa(Action::ToggleShip);
+        self.alternar_asistencia = activa(Action::ToggleFlightAssist);
+        self.alternar_raton = activa(Action::ToggleMouseLook);
+        self.alternar_gravedad = activa(Action::ToggleGravity);
+        self.alternar_diagnostico = activa(Action::ToggleDiagnostics);
+        self.salir = activa(Action::Quit);
+
+        let boton_izquierdo = window.get_mouse_down(MouseButton::Left);
+        self.clic = if boton_izquierdo && !self.boton_izquierdo_antes {
+            window.get_mouse_pos(MouseMode::Discard)
+        } else {
+            None
+        };
+        self.boton_izquierdo_antes = boton_izquierdo;
+
+        let boton_central = window.get_mouse_down(MouseButton::Middle);
+        self.restablecer_zoom = boton_central && !self.boton_central_antes;
+        self.boton_central_antes = boton_central;
+
+        self.zoom = match window.get_scroll_wheel() {
+            Some((_, y)) if y > 0.0 => 1,
+            Some((_, y)) if y < 0.0 => -1,
+            _ => 0,
+        };
+
+        // Con el diálogo de fecha abierto las teclas solo escriben la fecha
+        self.escribiendo_fecha = self.fecha.is_some();
+        if self.escribiendo_fecha {
+            self.alternar_raton = false;
+            self.soltar_controles_de_vuelo();
+        }
+        if self.alternar_raton {
+            self.raton_capturado = !self.raton_capturado;
+        }
+        self.actualizar_mirada_raton(window);
+
+        self.fecha_confirmada = None;
+        if self.escribiendo_fecha {
+            self.actualizar_fecha(window);
+        } else if self.ir_a_fecha {
+            self.fecha = Some(String::new());
+        }
+    }
+
+    /// Suelta las teclas de vuelo: la nave sigue con su inercia pero las
+    /// teclas que se escriben en el diálogo no la empujan ni la giran
+    fn soltar_controles_de_vuelo(&mut self) {
+        self.mover_adelante = false;
+        self.mover_atras = false;
+        self.mover_izquierda = false;
+        self.mover_derecha = false;
+        self.mover_arriba = false;
+        self.mover_abajo = false;
+        self.mirar_izquierda = false;
+        self.mirar_derecha = false;
+        self.mirar_arriba = false;
+        self.mirar_abajo = false;
+        self.girar_izquierda = false;
+        self.girar_derecha = false;
+        self.subir_potencia = false;
+        self.bajar_potencia = false;
+    }
+
+    /// Texto del diálogo de fecha, con lo que falta escribir como letras
+    pub fn texto_fecha(&self) -> Option<String> {
+        self.fecha.as_deref().map(rellenar_fecha)
+    }
+
+    /// Dígitos, borrar, Enter y Escape mientras el diálogo de fecha está abierto.
+    /// Se leen las teclas físicas, no el mapa de acciones.
+    fn actualizar_fecha(&mut self, window: &Window) {
+        let Some(digitos) = &mut self.fecha else {
+            return;
+        };
+
+        for key in window.get_keys_pressed(KeyRepeat::Yes) {
+            match key {
+                Key::Enter | Key::NumPadEnter => {
+                    self.fecha_confirmada = Some(confirmar_fecha(digitos));
+                    self.fecha = None;
+                    return;
+                }
+                Key::Escape => {
+                    self.fecha = None;
+                    return;
+                }
+                Key::Backspace => {
+                    digitos.pop();
+                }
+                _ => {
+                    if let Some(d) = digito(key)
+                        && digitos.len() < DIGITOS_FECHA
+                    {
+                        digitos.push(d);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Diferencia de posición del mouse respecto al frame anterior. minifb no
+    /// puede fijar el puntero, así que se lee también fuera de la ventana. Se
+    /// acumula hasta que la cámara la consume en un paso de simulación.
+    fn actualizar_mirada_raton(&mut self, window: &Window) {
+        if !self.raton_capturado {
+            self.mirar_raton = (0.0, 0.0);
+            self.raton_antes = None;
+            return;
+        }
+
+        let pos = window.get_mouse_pos(MouseMode::Pass);
+        if let (Some((x, y)), Some((x0, y0))) = (pos, self.raton_antes) {
+            // Mover a la derecha gira a la derecha; hacia arriba (y menor) mira arriba
+            let signo_y = if self.invertir_raton { -1.0 } else { 1.0 };
+            self.mirar_raton.0 -= (x - x0) * self.sensibilidad_raton;
+            self.mirar_raton.1 -= (y - y0) * self.sensibilidad_raton * signo_y;
+        }
+        self.raton_antes = pos;
+    }
+}
+
+/// Texto de la fecha al apretar Enter: hasta 8 dígitos, solo la fecha; si ya
+/// se empezó la hora, sus campos incompletos se rellenan con ceros a la
+/// izquierda ("202403201" -> "2024-03-20 01:00")
+fn confirmar_fecha(digitos: &str) -> String {
+    if digitos.len() <= 8 {
+        return rellenar_fecha(digitos)[..10].to_string();
+    }
+    let (fecha, hora) = digitos.split_at(8);
+    let (horas, minutos) = hora.split_at(hora.len().min(2));
+    rellenar_fecha(&format!("{}{:0>2}{:0>2}", fecha, horas, minutos))
+}
+
+/// Los dígitos escritos sobre la plantilla: "202403" -> "2024-03-DD HH:MM"
+fn rellenar_fecha(digitos: &str) -> String {
+    let mut digitos = digitos.chars();
+    PLANTILLA_FECHA
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphabetic() {
+                digitos.next().unwrap_or(c)
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Dígito de una tecla de la fila numérica o del teclado numérico
+fn digito(key: Key) -> Option<char> {
+    let n = match key {
+        Key::Key0 | Key::NumPad0 => 0,
+        Key::Key1 | Key::NumPad1 => 1,
+        Key::Key2 | Key::NumPad2 => 2,
+        Key::Key3 | Key::NumPad3 => 3,
+        Key::Key4 | Key::NumPad4 => 4,
+        Key::Key5 | Key::NumPad5 => 5,
+        Key::Key6 | Key::NumPad6 => 6,
+        Key::Key7 | Key::NumPad7 => 7,
+        Key::Key8 | Key::NumPad8 => 8,
+        Key::Key9 | Key::NumPad9 => 9,
+        _ => return None,
+    };
+    char::from_digit(n, 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confirmed_date_pads_the_time() {
+        assert_eq!(confirmar_fecha("20240320"), "2024-03-20");
+        assert_eq!(confirmar_fecha("202403201"), "2024-03-20 01:00");
+        assert_eq!(confirmar_fecha("2024032015"), "2024-03-20 15:00");
+        assert_eq!(confirmar_fecha("20240320153"), "2024-03-20 15:03");
+        assert_eq!(confirmar_fecha("202403201530"), "2024-03-20 15:30");
+        // Una fecha incompleta llega tal cual y la rechaza `parse_date`
+        assert_eq!(confirmar_fecha("202403"), "2024-03-DD");
     }
 }
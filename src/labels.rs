@@ -0,0 +1,173 @@
+use crate::camera::Camera;
+use crate::math::smoothstep;
+use crate::renderer::{Renderer, color, font};
+use crate::world::{BodyKind, SolarSystem};
+
+const TEXT_SCALE: i32 = 1;
+const LABEL_COLOR: u32 = 0xFFD8E4FF;
+const LEADER_COLOR: u32 = 0xFF8090C0;
+
+/// Separación entre el borde del cuerpo y la etiqueta
+const OFFSET: i32 = 14;
+/// Margen mínimo entre etiquetas
+const GAP: i32 = 3;
+
+/// La etiqueta se desvanece entre `FADE_START` y 1 veces esta distancia
+/// (multiplicada por el radio del cuerpo): los cuerpos chicos se apagan antes.
+const FADE_DISTANCE_PER_RADIUS: f32 = 150.0;
+const FADE_START: f32 = 0.6;
+
+/// Nombres de los cuerpos proyectados en pantalla con línea guía.
+pub struct Labels {
+    pub visible: bool,
+}
+
+struct Rect {
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+}
+
+impl Rect {
+    fn overlaps(&self, other: &Rect) -> bool {
+        self.x < other.x + other.w + GAP
+            && other.x < self.x + self.w + GAP
+            && self.y < other.y + other.h + GAP
+            && other.y < self.y + self.h + GAP
+    }
+}
+
+struct Candidate {
+    index: usize,
+    screen: (i32, i32),
+    radius_px: i32,
+    alpha: f32,
+    kind_rank: u8,
+    dist: f32,
+}
+
+impl Labels {
+    pub fn new() -> Self {
+        Self { visible: true }
+    }
+
+    pub fn draw(&self, renderer: &mut Renderer, system: &SolarSystem, camera: &Camera) {
+        if !self.visible {
+            return;
+        }
+
+        let mut candidates: Vec<Candidate> = Vec::new();
+        for (i, body) in system.bodies.iter().enumerate() {
+            let Some((screen, radius_px)) = system.project_body(i, renderer, camera) else {
+                continue;
+            };
+
+            let dist = (system.body_position(i) - camera.position).length();
+            let fade_end = body.radius * FADE_DISTANCE_PER_RADIUS;
+            let alpha = 1.0 - smoothstep(fade_end * FADE_START, fade_end, dist);
+            if alpha <= 0.02 {
+                continue;
+            }
+
+            // Estrellas primero, luego lo más cercano a la cámara
+            let kind_rank = match body.kind {
+                BodyKind::Star => 0,
+                BodyKind::Planet => 1,
+                BodyKind::Moon => 2,
+            };
+            candidates.push(Candidate {
+                index: i,
+                screen,
+                radius_px,
+                alpha,
+                kind_rank,
+                dist,
+            });
+        }
+        candidates.sort_by(|a, b| {
+            a.kind_rank
+                .cmp(&b.kind_rank)
+                .then_with(|| a.dist.total_cmp(&b.dist))
+        });
+
+        // Ubicación voraz: cada etiqueta prueba varias posiciones alrededor de su
+        // cuerpo y se queda con la primera que no choca con las ya colocadas.
+        let mut placed: Vec<Rect> = Vec::new();
+        let text_h = font::GLYPH_H * TEXT_SCALE;
+
+        for c in candidates {
+            let name = &system.bodies[c.index].name;
+            let text_w = font::text_width(name, TEXT_SCALE);
+            let (sx, sy) = c.screen;
+            let edge = (c.radius_px as f32 * 0.7) as i32;
+
+            let slots = [
+                (1, -1, 0),
+                (1, 1, 0),
+                (-1, -1, 0),
+                (-1, 1, 0),
+                (1, -1, 1),
+                (-1, 1, 1),
+            ];
+
+            let chosen = slots.iter().find_map(|&(dx, dy, step)| {
+                let reach = OFFSET + step * (text_h + 2 * GAP);
+                let anchor = (sx + dx * edge, sy + dy * edge);
+                let x = if dx > 0 {
+                    anchor.0 + reach
+                } else {
+                    anchor.0 - reach - text_w
+                };
+                let y = if dy < 0 {
+                    anchor.1 - reach - text_h
+                } else {
+                    anchor.1 + reach
+                };
+                let rect = Rect {
+                    x,
+                    y,
+                    w: text_w,
+                    h: text_h,
+                };
+
+                let on_screen = rect.x >= 0
+                    && rect.y >= 0
+                    && rect.x + rect.w < renderer.width as i32
+                    && rect.y + rect.h < renderer.height as i32;
+                if !on_screen || placed.iter().any(|p| p.overlaps(&rect)) {
+                    return None;
+                }
+
+                // La línea guía llega a la esquina del texto más cercana al cuerpo
+                let corner = (
+                    if dx > 0 {
+                        rect.x - 2
+                    } else {
+                        rect.x + rect.w + 1
+                    },
+                    if dy < 0 { rect.y + rect.h } else { rect.y - 1 },
+                );
+                Some((rect, anchor, corner))
+            });
+
+            let Some((rect, anchor, corner)) = chosen else {
+                continue;
+            };
+
+            renderer.draw_line(
+                anchor,
+                corner,
+                color::with_alpha(LEADER_COLOR, c.alpha * 0.8),
+            );
+            renderer.draw_text_shadowed(
+                rect.x,
+                rect.y,
+                name,
+                color::with_alpha(LABEL_COLOR, c.alpha),
+                TEXT_SCALE,
+            );
+            placed.push(rect);
+        }
+    }
+}
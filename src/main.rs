@@ -1,4 +1,5 @@
 mod app;
+mod bindings;
 mod renderer;
 mod math;
 mod world;
@@ -7,11 +8,59 @@ mod warp;
 mod skybox;
 mod collision;
 mod input;
-mod texture;   
+mod texture;
+mod sim_clock;
+mod view;
+mod cli;
+mod headless;
+mod calendar;
+mod capture;
+mod hud;
+mod labels;
+mod orbit_camera;
+mod ship;
+mod timestep;
 
 use app::App;
+use bindings::Bindings;
+use cli::Options;
+use world::Scene;
 
 fn main() {
-    let mut app = App::new(800, 600);
+    let opts = Options::parse(std::env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    });
+
+    let mut scene = Scene::load(&opts.scene, opts.scale).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    if let Some(jd) = opts.date {
+        scene.system.set_date(jd);
+    }
+
+    if opts.headless {
+        if let Err(e) = headless::run(scene, &opts) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Sin --keys se usa controles.toml si existe, y si no los controles por defecto
+    let bindings = match &opts.keys {
+        Some(path) => Bindings::load(path),
+        None if std::path::Path::new(bindings::DEFAULT_BINDINGS).exists() => {
+            Bindings::load(bindings::DEFAULT_BINDINGS)
+        }
+        None => Ok(Bindings::new()),
+    }
+    .unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+
+    let mut app = App::new(&opts, scene, bindings);
     app.run();
 }
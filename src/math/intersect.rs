@@ -0,0 +1,24 @@
+use super::Vec3;
+
+/// Intersección rayo-esfera. `dir` debe estar normalizado.
+/// Devuelve la distancia `t >= 0` al primer punto de contacto sobre el rayo
+/// (0 si el origen ya está dentro de la esfera).
+pub fn ray_sphere(origin: Vec3, dir: Vec3, center: Vec3, radius: f32) -> Option<f32> {
+    let oc = origin - center;
+    let b = oc.dot(dir);
+    let c = oc.dot(oc) - radius * radius;
+
+    if c <= 0.0 {
+        return Some(0.0);
+    }
+    // Fuera de la esfera y alejándose
+    if b > 0.0 {
+        return None;
+    }
+
+    let disc = b * b - c;
+    if disc < 0.0 {
+        return None;
+    }
+    Some(-b - disc.sqrt())
+}
@@ -1,3 +1,13 @@
+pub mod intersect;
+pub mod quat;
 pub mod vec;
 
+pub use intersect::ray_sphere;
+pub use quat::Quat;
 pub use vec::{Vec2, Vec3};
+
+/// Interpolación suave de Hermite: 0 si x <= e0, 1 si x >= e1.
+pub fn smoothstep(e0: f32, e1: f32, x: f32) -> f32 {
+    let t = ((x - e0) / (e1 - e0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
@@ -0,0 +1,195 @@
+use std::ops::Mul;
+
+use super::Vec3;
+
+/// Cuaternión unitario para orientaciones 3D (w + xi + yj + zk).
+#[derive(Clone, Copy, Debug)]
+pub struct Quat {
+    pub w: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Quat {
+    pub fn identity() -> Self {
+        Self { w: 1.0, x: 0.0, y: 0.0, z: 0.0 }
+    }
+
+    /// Rotación de `angle` radianes alrededor de `axis` (regla de la mano derecha)
+    pub fn from_axis_angle(axis: Vec3, angle: f32) -> Self {
+        let a = axis.normalized();
+        let (s, c) = (angle * 0.5).sin_cos();
+        Self { w: c, x: a.x * s, y: a.y * s, z: a.z * s }
+    }
+
+    /// Orientación cuyo -Z local apunta a `forward` y cuyo +Y local queda lo más
+    /// cerca posible de `up`. Si son paralelos se elige otro "arriba".
+    pub fn look_rotation(forward: Vec3, up: Vec3) -> Self {
+        let f = forward.normalized();
+        let mut r = f.cross(up);
+        if r.length() < 1e-4 {
+            let alt = if f.y.abs() < 0.9 { Vec3::up() } else { Vec3::new(0.0, 0.0, 1.0) };
+            r = f.cross(alt);
+        }
+        let r = r.normalized();
+        let u = r.cross(f);
+
+        // Matriz de rotación con columnas (r, u, -f)
+        let (m00, m01, m02) = (r.x, u.x, -f.x);
+        let (m10, m11, m12) = (r.y, u.y, -f.y);
+        let (m20, m21, m22) = (r.z, u.z, -f.z);
+
+        let trace = m00 + m11 + m22;
+        let q = if trace > 0.0 {
+            let s = (trace + 1.0).sqrt() * 2.0;
+            Self { w: 0.25 * s, x: (m21 - m12) / s, y: (m02 - m20) / s, z: (m10 - m01) / s }
+        } else if m00 > m11 && m00 > m22 {
+            let s = (1.0 + m00 - m11 - m22).sqrt() * 2.0;
+            Self { w: (m21 - m12) / s, x: 0.25 * s, y: (m01 + m10) / s, z: (m02 + m20) / s }
+        } else if m11 > m22 {
+            let s = (1.0 + m11 - m00 - m22).sqrt() * 2.0;
+            Self { w: (m02 - m20) / s, x: (m01 + m10) / s, y: 0.25 * s, z: (m12 + m21) / s }
+        } else {
+            let s = (1.0 + m22 - m00 - m11).sqrt() * 2.0;
+            Self { w: (m10 - m01) / s, x: (m02 + m20) / s, y: (m12 + m21) / s, z: 0.25 * s }
+        };
+        q.normalized()
+    }
+
+    /// Rotación inversa (para un cuaternión unitario)
+    pub fn conjugate(self) -> Self {
+        Self { w: self.w, x: -self.x, y: -self.y, z: -self.z }
+    }
+
+    pub fn dot(self, other: Quat) -> f32 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn normalized(self) -> Self {
+        let len = self.dot(self).sqrt();
+        if len == 0.0 {
+            Self::identity()
+        } else {
+            Self { w: self.w / len, x: self.x / len, y: self.y / len, z: self.z / len }
+        }
+    }
+
+    /// Aplica la rotación a un vector
+    pub fn rotate(self, v: Vec3) -> Vec3 {
+        let q = Vec3::new(self.x, self.y, self.z);
+        let t = q.cross(v) * 2.0;
+        v + t * self.w + q.cross(t)
+    }
+
+    /// Interpolación esférica por el camino más corto
+    pub fn slerp(self, other: Quat, t: f32) -> Quat {
+        let mut other = other;
+        let mut cos = self.dot(other);
+        if cos < 0.0 {
+            other = Quat { w: -other.w, x: -other.x, y: -other.y, z: -other.z };
+            cos = -cos;
+        }
+
+        // Casi iguales: basta con interpolar linealmente y normalizar
+        let (k0, k1) = if cos > 0.9995 {
+            (1.0 - t, t)
+        } else {
+            let theta = cos.acos();
+            let sin = theta.sin();
+            (((1.0 - t) * theta).sin() / sin, (t * theta).sin() / sin)
+        };
+
+        Quat {
+            w: self.w * k0 + other.w * k1,
+            x: self.x * k0 + other.x * k1,
+            y: self.y * k0 + other.y * k1,
+            z: self.z * k0 + other.z * k1,
+        }
+        .normalized()
+    }
+}
+
+impl Mul for Quat {
+    type Output = Quat;
+    /// Composición: `a * b` aplica primero `b` y luego `a`
+    fn mul(self, b: Quat) -> Quat {
+        let a = self;
+        Quat {
+            w: a.w * b.w - a.x * b.x - a.y * b.y - a.z * b.z,
+            x: a.w * b.x + a.x * b.w + a.y * b.z - a.z * b.y,
+            y: a.w * b.y - a.x * b.z + a.y * b.w + a.z * b.x,
+            z: a.w * b.z + a.x * b.y - a.y * b.x + a.z * b.w,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn close(a: Vec3, b: Vec3) -> bool {
+        (a - b).length() < 1e-5
+    }
+
+    /// q y -q son la misma rotación
+    fn same_rotation(a: Quat, b: Quat) -> bool {
+        (a.dot(b).abs() - 1.0).abs() < 1e-5
+    }
+
+    #[test]
+    fn axis_angle_rotates_right_handed() {
+        let q = Quat::from_axis_angle(Vec3::up(), FRAC_PI_2);
+        assert!(close(q.rotate(Vec3::new(1.0, 0.0, 0.0)), Vec3::new(0.0, 0.0, -1.0)));
+        // El eje no se mueve
+        let axis = Vec3::new(1.0, 2.0, -0.5).normalized();
+        assert!(close(Quat::from_axis_angle(axis, 1.3).rotate(axis), axis));
+    }
+
+    #[test]
+    fn axis_angle_round_trip() {
+        let axis = Vec3::new(0.3, -1.0, 0.7);
+        let v = Vec3::new(2.0, -1.0, 0.5);
+        for angle in [0.1, 1.0, 2.5, -3.0] {
+            let q = Quat::from_axis_angle(axis, angle);
+            let back = Quat::from_axis_angle(axis, -angle);
+            assert!(close((back * q).rotate(v), v));
+            assert!(same_rotation(back * q, Quat::identity()));
+            // Dos medios giros hacen el giro completo
+            let half = Quat::from_axis_angle(axis, angle / 2.0);
+            assert!(same_rotation(half * half, q));
+        }
+    }
+
+    #[test]
+    fn normalize_gives_unit_length() {
+        let q = Quat { w: 2.0, x: -1.0, y: 0.5, z: 3.0 }.normalized();
+        assert!((q.dot(q) - 1.0).abs() < 1e-6);
+        let zero = Quat { w: 0.0, x: 0.0, y: 0.0, z: 0.0 };
+        assert!(same_rotation(zero.normalized(), Quat::identity()));
+    }
+
+    #[test]
+    fn slerp_endpoints_and_midpoint() {
+        let a = Quat::from_axis_angle(Vec3::up(), 0.2);
+        let b = Quat::from_axis_angle(Vec3::up(), 1.4);
+        assert!(same_rotation(a.slerp(b, 0.0), a));
+        assert!(same_rotation(a.slerp(b, 1.0), b));
+        assert!(same_rotation(a.slerp(b, 0.5), Quat::from_axis_angle(Vec3::up(), 0.8)));
+        // Camino corto aunque el otro extremo tenga el signo opuesto
+        let neg_b = Quat { w: -b.w, x: -b.x, y: -b.y, z: -b.z };
+        assert!(same_rotation(a.slerp(neg_b, 0.5), a.slerp(b, 0.5)));
+        assert!((a.slerp(b, 0.3).dot(a.slerp(b, 0.3)) - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn look_rotation_points_forward() {
+        let dir = Vec3::new(1.0, 0.5, -2.0).normalized();
+        let q = Quat::look_rotation(dir, Vec3::up());
+        assert!(close(q.rotate(Vec3::new(0.0, 0.0, -1.0)), dir));
+        // Mirando justo hacia arriba tampoco falla
+        let q = Quat::look_rotation(Vec3::up(), Vec3::up());
+        assert!(close(q.rotate(Vec3::new(0.0, 0.0, -1.0)), Vec3::up()));
+    }
+}
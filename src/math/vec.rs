@@ -1,11 +1,13 @@
 use std::ops::{Add, Sub, Mul, Div};
 
+#[allow(dead_code)]
 #[derive(Clone, Copy, Debug)]
 pub struct Vec2 {
     pub x: f32,
     pub y: f32,
 }
 
+#[allow(dead_code)]
 impl Vec2 {
     pub fn new(x: f32, y: f32) -> Self {
         Self { x, y }
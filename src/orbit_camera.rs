@@ -0,0 +1,100 @@
+use crate::camera::Camera;
+use crate::collision;
+use crate::input::InputState;
+use crate::math::Vec3;
+
+const ROT_SPEED: f32 = 1.5;
+/// Factor de acercamiento por segundo con Q/E
+const ZOOM_SPEED: f32 = 1.8;
+const MAX_ELEVATION: f32 = 1.2;
+const MAX_DISTANCE: f32 = 2000.0;
+/// Con cuerpos grandes (p. ej. a escala real) la distancia máxima crece con el radio
+const MAX_DISTANCE_RADII: f32 = 200.0;
+
+/// Cámara en órbita alrededor de un cuerpo: mantiene un desplazamiento fijo
+/// (azimut, elevación, distancia) respecto al centro del cuerpo, que se vuelve
+/// a calcular cada frame aunque el cuerpo se mueva.
+pub struct OrbitRig {
+    pub target: usize,
+    pub azimuth: f32,
+    pub elevation: f32,
+    pub distance: f32,
+    min_distance: f32,
+    max_distance: f32,
+}
+
+impl OrbitRig {
+    /// Arranca desde la posición actual de la cámara para no dar saltos
+    pub fn from_camera(target: usize, center: Vec3, radius: f32, camera: &Camera) -> Self {
+        let offset = camera.position - center;
+        // Fuera de la capa de choque, o la colisión empujaría a la cámara cada frame
+        let min_distance = (radius * 1.5).max(radius + collision::MARGIN);
+        let max_distance = MAX_DISTANCE.max(radius * MAX_DISTANCE_RADII).max(min_distance);
+        let distance = offset.length().clamp(min_distance, max_distance);
+        let dir = offset.normalized();
+
+        Self {
+            target,
+            azimuth: dir.x.atan2(dir.z),
+            elevation: dir.y.clamp(-1.0, 1.0).asin().clamp(-MAX_ELEVATION, MAX_ELEVATION),
+            distance,
+            min_distance,
+            max_distance,
+        }
+    }
+
+    /// Flechas (o el mouse capturado) giran alrededor del cuerpo; Q aleja y E acerca
+    pub fn update(&mut self, dt: f32, input: &InputState) {
+        let (yaw, pitch) = input.mirar_raton;
+        self.azimuth -= yaw;
+        self.elevation += pitch;
+
+        if input.mirar_izquierda {
+            self.azimuth -= ROT_SPEED * dt;
+        }
+        if input.mirar_derecha {
+            self.azimuth += ROT_SPEED * dt;
+        }
+        if input.mirar_arriba {
+            self.elevation += ROT_SPEED * dt;
+        }
+        if input.mirar_abajo {
+            self.elevation -= ROT_SPEED * dt;
+        }
+        self.elevation = self.elevation.clamp(-MAX_ELEVATION, MAX_ELEVATION);
+
+        if input.mover_arriba {
+            self.distance /= ZOOM_SPEED.powf(dt);
+        }
+        if input.mover_abajo {
+            self.distance *= ZOOM_SPEED.powf(dt);
+        }
+        self.distance = self.distance.clamp(self.min_distance, self.max_distance);
+    }
+
+    /// Coloca la cámara respecto al centro actual del cuerpo y la hace mirarlo
+    pub fn apply(&self, center: Vec3, camera: &mut Camera) {
+        let (se, ce) = self.elevation.sin_cos();
+        let (sa, ca) = self.azimuth.sin_cos();
+        let offset = Vec3::new(ce * sa, se, ce * ca) * self.distance;
+
+        camera.position = center + offset;
+        camera.look_at(center);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance_limits_follow_the_radius() {
+        let camera = Camera::new();
+        for radius in [0.5, 10.0, 1500.0, 7.0e5] {
+            let rig = OrbitRig::from_camera(0, Vec3::zero(), radius, &camera);
+            assert!(rig.min_distance >= radius + collision::MARGIN, "radio {}", radius);
+            assert!(rig.min_distance <= rig.max_distance, "radio {}", radius);
+            assert!(rig.distance >= rig.min_distance && rig.distance <= rig.max_distance);
+        }
+    }
+}
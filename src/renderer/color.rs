@@ -0,0 +1,45 @@
+//! Utilidades para colores empaquetados como 0xAARRGGBB.
+
+fn channels(color: u32) -> (u32, u32, u32, u32) {
+    (
+        (color >> 24) & 0xFF,
+        (color >> 16) & 0xFF,
+        (color >> 8) & 0xFF,
+        color & 0xFF,
+    )
+}
+
+fn pack(a: u32, r: f32, g: f32, b: f32) -> u32 {
+    let r = r.clamp(0.0, 255.0) as u32;
+    let g = g.clamp(0.0, 255.0) as u32;
+    let b = b.clamp(0.0, 255.0) as u32;
+    (a << 24) | (r << 16) | (g << 8) | b
+}
+
+/// Multiplica el RGB por `k` (conserva el alfa).
+pub fn scale(color: u32, k: f32) -> u32 {
+    let (a, r, g, b) = channels(color);
+    pack(a, r as f32 * k, g as f32 * k, b as f32 * k)
+}
+
+/// Suma saturada de dos colores (conserva el alfa de `a`).
+pub fn add(a: u32, b: u32) -> u32 {
+    let (aa, ar, ag, ab) = channels(a);
+    let (_, br, bg, bb) = channels(b);
+    pack(aa, (ar + br) as f32, (ag + bg) as f32, (ab + bb) as f32)
+}
+
+/// Mezcla `src` sobre `dst` usando el alfa de `src` (el resultado es opaco).
+pub fn blend(dst: u32, src: u32) -> u32 {
+    let (sa, sr, sg, sb) = channels(src);
+    let (_, dr, dg, db) = channels(dst);
+    let t = sa as f32 / 255.0;
+    let mix = |d: u32, s: u32| d as f32 + (s as f32 - d as f32) * t;
+    pack(0xFF, mix(dr, sr), mix(dg, sg), mix(db, sb))
+}
+
+/// Reemplaza el alfa del color por `alpha` (0..1)
+pub fn with_alpha(color: u32, alpha: f32) -> u32 {
+    let a = (alpha.clamp(0.0, 1.0) * 255.0) as u32;
+    (color & 0x00FF_FFFF) | (a << 24)
+}
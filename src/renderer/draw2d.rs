@@ -9,6 +9,7 @@ impl<'a> Draw2D<'a> {
         Self { fb }
     }
 
+    #[allow(dead_code)]
     pub fn filled_circle(&mut self, center: (i32, i32), radius: i32, color: u32) {
         let (cx, cy) = center;
         let r2 = radius * radius;
@@ -48,6 +49,7 @@ impl<'a> Draw2D<'a> {
         }
     }
 
+    /// Línea de Bresenham; se mezcla con el fondo según el alfa de `color`.
     pub fn line(&mut self, p0: (i32, i32), p1: (i32, i32), color: u32) {
         let (mut x0, mut y0) = p0;
         let (x1, y1) = p1;
@@ -59,7 +61,7 @@ impl<'a> Draw2D<'a> {
         let mut err = dx + dy;
 
         loop {
-            self.fb.put_pixel(x0, y0, color);
+            self.fb.blend_pixel(x0, y0, color);
 
             if x0 == x1 && y0 == y1 {
                 break;
@@ -77,9 +79,101 @@ impl<'a> Draw2D<'a> {
         }
     }
 
+    /// Igual que `line`, pero interpolando la profundidad entre los extremos
+    /// y respetando el z-buffer. Los extremos van en píxeles sin redondear y el
+    /// segmento se recorta a la imagen antes de recorrerlo: un extremo
+    /// proyectado muy fuera de pantalla no cuesta millones de pasos.
+    pub fn line_depth(&mut self, p0: (f32, f32), z0: f32, p1: (f32, f32), z1: f32, color: u32) {
+        let max = (self.fb.width as f32 - 1.0, self.fb.height as f32 - 1.0);
+        let Some((t0, t1)) = clip_segment(p0, p1, max) else {
+            return;
+        };
+        let at = |t: f32| {
+            (
+                p0.0 + (p1.0 - p0.0) * t,
+                p0.1 + (p1.1 - p0.1) * t,
+                z0 + (z1 - z0) * t,
+            )
+        };
+        let (x0, y0, z0) = at(t0);
+        let (x1, y1, z1) = at(t1);
+
+        let steps = (x1 - x0).abs().max((y1 - y0).abs()).ceil().max(1.0) as i32;
+
+        for i in 0..=steps {
+            let t = i as f32 / steps as f32;
+            let x = (x0 + (x1 - x0) * t).round() as i32;
+            let y = (y0 + (y1 - y0) * t).round() as i32;
+            let z = z0 + (z1 - z0) * t;
+            self.fb.put_pixel_depth(x, y, z, color);
+        }
+    }
+
+    #[allow(dead_code)]
     pub fn triangle(&mut self, p0: (i32, i32), p1: (i32, i32), p2: (i32, i32), color: u32) {
         self.line(p0, p1, color);
         self.line(p1, p2, color);
         self.line(p2, p0, color);
     }
 }
+
+/// Liang–Barsky: tramo [t0, t1] del segmento `p0`→`p1` que cae dentro del
+/// rectángulo [0, max.0] × [0, max.1], o `None` si no lo toca
+fn clip_segment(p0: (f32, f32), p1: (f32, f32), max: (f32, f32)) -> Option<(f32, f32)> {
+    let (dx, dy) = (p1.0 - p0.0, p1.1 - p0.1);
+    let (mut t0, mut t1) = (0.0f32, 1.0f32);
+
+    for (p, q) in [
+        (-dx, p0.0),
+        (dx, max.0 - p0.0),
+        (-dy, p0.1),
+        (dy, max.1 - p0.1),
+    ] {
+        if p == 0.0 {
+            // Paralelo a este borde: o está todo del lado de adentro o nada
+            if q < 0.0 {
+                return None;
+            }
+            continue;
+        }
+        let r = q / p;
+        if p < 0.0 {
+            t0 = t0.max(r);
+        } else {
+            t1 = t1.min(r);
+        }
+        if t0 > t1 {
+            return None;
+        }
+    }
+    Some((t0, t1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clips_to_the_rectangle() {
+        let max = (99.0, 49.0);
+        assert_eq!(
+            clip_segment((10.0, 10.0), (20.0, 30.0), max),
+            Some((0.0, 1.0))
+        );
+        assert_eq!(
+            clip_segment((-100.0, 10.0), (100.0, 10.0), max),
+            Some((0.5, 0.995))
+        );
+        assert_eq!(clip_segment((-10.0, -10.0), (-5.0, 60.0), max), None);
+        assert!(clip_segment((-10.0, 60.0), (60.0, -20.0), max).is_some());
+    }
+
+    #[test]
+    fn huge_lines_draw_only_the_visible_part() {
+        let mut fb = FrameBuffer::new(100, 50);
+        Draw2D::new(&mut fb).line_depth((-1.0e9, 25.0), 1.0, (1.0e9, 25.0), 1.0, 0xFFFFFFFF);
+        let row = &fb.pixels[25 * 100..26 * 100];
+        assert!(row.iter().all(|&c| c == 0xFFFFFFFF));
+        assert_eq!(fb.pixels.iter().filter(|&&c| c != 0).count(), 100);
+    }
+}
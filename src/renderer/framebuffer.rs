@@ -2,6 +2,8 @@ pub struct FrameBuffer {
     pub width: usize,
     pub height: usize,
     pub pixels: Vec<u32>,
+    /// Profundidad por píxel (distancia en el eje de vista). `INFINITY` = vacío.
+    pub depth: Vec<f32>,
 }
 
 impl FrameBuffer {
@@ -10,6 +12,7 @@ impl FrameBuffer {
             width,
             height,
             pixels: vec![0x000000; width * height],
+            depth: vec![f32::INFINITY; width * height],
         }
     }
 
@@ -17,19 +20,58 @@ impl FrameBuffer {
         for p in &mut self.pixels {
             *p = color;
         }
+        for d in &mut self.depth {
+            *d = f32::INFINITY;
+        }
     }
 
-    pub fn put_pixel(&mut self, x: i32, y: i32, color: u32) {
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
         if x < 0 || y < 0 {
-            return;
+            return None;
         }
         let x = x as usize;
         let y = y as usize;
 
         if x >= self.width || y >= self.height {
-            return;
+            return None;
+        }
+
+        Some(y * self.width + x)
+    }
+
+    pub fn put_pixel(&mut self, x: i32, y: i32, color: u32) {
+        if let Some(i) = self.index(x, y) {
+            self.pixels[i] = color;
         }
+    }
+
+    /// Dibuja `color` sobre el píxel existente usando su canal alfa como opacidad.
+    pub fn blend_pixel(&mut self, x: i32, y: i32, color: u32) {
+        if let Some(i) = self.index(x, y) {
+            self.pixels[i] = super::color::blend(self.pixels[i], color);
+        }
+    }
+
+    /// Escribe el píxel solo si `z` está más cerca que lo ya dibujado.
+    /// Devuelve `true` si el píxel pasó la prueba de profundidad.
+    pub fn put_pixel_depth(&mut self, x: i32, y: i32, z: f32, color: u32) -> bool {
+        match self.index(x, y) {
+            Some(i) if z < self.depth[i] => {
+                self.depth[i] = z;
+                self.pixels[i] = color;
+                true
+            }
+            _ => false,
+        }
+    }
 
-        self.pixels[y * self.width + x] = color;
+    /// Guarda el contenido de color como PNG.
+    pub fn save_png(&self, path: &str) -> Result<(), String> {
+        let img = image::RgbImage::from_fn(self.width as u32, self.height as u32, |x, y| {
+            let c = self.pixels[y as usize * self.width + x as usize];
+            image::Rgb([(c >> 16) as u8, (c >> 8) as u8, c as u8])
+        });
+        img.save_with_format(path, image::ImageFormat::Png)
+            .map_err(|e| format!("No se pudo guardar {}: {}", path, e))
     }
 }
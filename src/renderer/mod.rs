@@ -1,16 +1,56 @@
 pub mod framebuffer;
 pub mod draw2d;
+pub mod color;
+pub mod font;
 
 use framebuffer::FrameBuffer;
 use draw2d::Draw2D;
 
+use std::f32::consts::PI;
+
 use crate::camera::Camera;
-use crate::math::{Vec2, Vec3};
+use crate::math::{smoothstep, Quat, Vec2, Vec3};
 use crate::texture::Texture;
 
+/// Texturas de la superficie de un cuerpo esférico.
+pub struct SphereSurface<'a> {
+    /// Mapa diurno (albedo); sin textura se usa `color`
+    pub day: Option<&'a Texture>,
+    /// Color sólido cuando no hay mapa diurno
+    pub color: u32,
+    /// Mapa emisivo opcional que se ve en el hemisferio nocturno (luces de ciudades)
+    pub night: Option<&'a Texture>,
+}
+
+/// Orientación de un cuerpo: lleva ejes del cuerpo (polo en +Y, longitud 0
+/// sobre +X) a ejes del mundo.
+#[derive(Clone, Copy)]
+pub struct Spin {
+    pub orientation: Quat,
+}
+
+impl Spin {
+    /// Giro `angle` sobre su polo, inclinado `tilt` respecto a +Y (el polo se
+    /// inclina hacia +Z, girando alrededor de X)
+    pub fn tilted(angle: f32, tilt: f32) -> Self {
+        let tilt = Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), tilt);
+        Self { orientation: tilt * Quat::from_axis_angle(Vec3::up(), angle) }
+    }
+
+    /// Lleva una dirección del mundo al espacio del cuerpo
+    fn to_local(self, world: Vec3) -> Vec3 {
+        self.orientation.conjugate().rotate(world)
+    }
+}
+
+/// Distancia del plano cercano: lo que esté más cerca de la cámara no se dibuja
+const NEAR: f32 = 0.1;
+
 pub struct Renderer {
     pub width: usize,
     pub height: usize,
+    /// Luz ambiente mínima en el lado nocturno de los cuerpos iluminados (0..1)
+    pub ambient: f32,
     fb: FrameBuffer,
 }
 
@@ -19,6 +59,7 @@ impl Renderer {
         Self {
             width,
             height,
+            ambient: 0.06,
             fb: FrameBuffer::new(width, height),
         }
     }
@@ -31,10 +72,21 @@ impl Renderer {
         &self.fb.pixels
     }
 
+    /// Guarda el frame actual como PNG
+    pub fn save_png(&self, path: &str) -> Result<(), String> {
+        self.fb.save_png(path)
+    }
+
     pub fn put_pixel(&mut self, x: i32, y: i32, color: u32) {
         self.fb.put_pixel(x, y, color);
     }
 
+    /// `put_pixel` con prueba de profundidad contra el z-buffer.
+    pub fn put_pixel_depth(&mut self, x: i32, y: i32, z: f32, color: u32) -> bool {
+        self.fb.put_pixel_depth(x, y, z, color)
+    }
+
+    #[allow(dead_code)]
     pub fn draw_filled_circle(&mut self, center: (i32, i32), radius: i32, color: u32) {
         let mut d = Draw2D::new(&mut self.fb);
         d.filled_circle(center, radius, color);
@@ -50,36 +102,139 @@ impl Renderer {
         d.line(p0, p1, color);
     }
 
+    /// Segmento 3D con profundidad; se oculta detrás de lo ya dibujado. Se
+    /// recorta contra el plano cercano antes de proyectar, así un extremo
+    /// detrás de la cámara no se pierde ni se proyecta al infinito.
+    pub fn draw_line_depth(&mut self, a: Vec3, b: Vec3, camera: &Camera, color: u32) {
+        let (forward, right, up) = camera.basis();
+        let to_camera = |p: Vec3| {
+            let rel = p - camera.position;
+            Vec3::new(rel.dot(right), rel.dot(up), rel.dot(forward))
+        };
+        let (mut a, mut b) = (to_camera(a), to_camera(b));
+        if a.z < NEAR && b.z < NEAR {
+            return;
+        }
+        if a.z < NEAR {
+            a = b.lerp(a, (b.z - NEAR) / (b.z - a.z));
+        } else if b.z < NEAR {
+            b = a.lerp(b, (a.z - NEAR) / (a.z - b.z));
+        }
+
+        let f = self.focal_length(camera);
+        let (cx, cy) = (self.width as f32 / 2.0, self.height as f32 / 2.0);
+        let screen = |p: Vec3| (cx + p.x * f / p.z, cy - p.y * f / p.z);
+        let mut d = Draw2D::new(&mut self.fb);
+        d.line_depth(screen(a), a.z, screen(b), b.z, color);
+    }
+
+    #[allow(dead_code)]
     pub fn draw_triangle(&mut self, p0: (i32, i32), p1: (i32, i32), p2: (i32, i32), color: u32) {
         let mut d = Draw2D::new(&mut self.fb);
         d.triangle(p0, p1, p2, color);
     }
 
-    /// Proyección 3D: mundo -> pantalla (VERSIÓN ORIGINAL QUE YA FUNCIONABA)
+    /// Escribe texto con la fuente de mapa de bits; (x, y) es la esquina superior
+    /// izquierda. El alfa de `color` es la opacidad del texto.
+    pub fn draw_text(&mut self, x: i32, y: i32, text: &str, color: u32, scale: i32) {
+        let mut pen_x = x;
+        for c in text.chars() {
+            let rows = font::glyph(c);
+            for (row, bits) in rows.iter().enumerate() {
+                for col in 0..font::GLYPH_W {
+                    if bits & (0x10 >> col) == 0 {
+                        continue;
+                    }
+                    let px = pen_x + col * scale;
+                    let py = y + row as i32 * scale;
+                    for dy in 0..scale {
+                        for dx in 0..scale {
+                            self.fb.blend_pixel(px + dx, py + dy, color);
+                        }
+                    }
+                }
+            }
+            pen_x += font::ADVANCE * scale;
+        }
+    }
+
+    /// `draw_text` con una sombra oscura desplazada 1 px para leerse sobre cualquier fondo
+    pub fn draw_text_shadowed(&mut self, x: i32, y: i32, text: &str, color: u32, scale: i32) {
+        let shadow = color & 0xFF00_0000;
+        self.draw_text(x + scale, y + scale, text, shadow, scale);
+        self.draw_text(x, y, text, color, scale);
+    }
+
+    /// Rectángulo relleno mezclado con el alfa de `color` (paneles del HUD)
+    pub fn fill_rect(&mut self, x: i32, y: i32, w: i32, h: i32, color: u32) {
+        for py in y..y + h {
+            for px in x..x + w {
+                self.fb.blend_pixel(px, py, color);
+            }
+        }
+    }
+
+    /// Distancia focal en píxeles para el FOV vertical de la cámara
+    fn focal_length(&self, camera: &Camera) -> f32 {
+        (self.height as f32 / 2.0) / (camera.fov_y * 0.5).tan()
+    }
+
+    /// Proyección 3D: mundo -> pantalla
+    #[allow(dead_code)]
     pub fn project_point(&self, world: Vec3, camera: &Camera) -> Option<(i32, i32)> {
+        self.project_point_depth(world, camera).map(|(p, _)| p)
+    }
+
+    /// Igual que `project_point`, pero devuelve también la profundidad
+    /// (distancia sobre el eje de vista) para el z-buffer.
+    pub fn project_point_depth(&self, world: Vec3, camera: &Camera) -> Option<((i32, i32), f32)> {
         let rel = world - camera.position;
 
-        let forward = camera.forward();
-        let right = forward.cross(Vec3::up()).normalized();
-        let up = right.cross(forward).normalized();
+        let (forward, right, up) = camera.basis();
 
         let x_cam = rel.dot(right);
         let y_cam = rel.dot(up);
-        let z_cam = -rel.dot(forward);
+        let z_cam = rel.dot(forward);
 
         // Si está demasiado cerca o detrás, no se dibuja
-        if z_cam <= 0.1 {
+        if z_cam <= NEAR {
             return None;
         }
 
-        let f = (self.height as f32 / 2.0) / (camera.fov_y * 0.5).tan();
+        let f = self.focal_length(camera);
 
         let sx = self.width as f32 / 2.0 + x_cam * f / z_cam;
         let sy = self.height as f32 / 2.0 - y_cam * f / z_cam;
 
-        Some((sx as i32, sy as i32))
+        Some(((sx as i32, sy as i32), z_cam))
     }
 
+    /// Inversa de la proyección: dirección (normalizada, en mundo) del rayo que
+    /// sale de la cámara y pasa por el píxel (x, y).
+    pub fn screen_ray(&self, x: f32, y: f32, camera: &Camera) -> Vec3 {
+        let (forward, right, up) = camera.basis();
+        let f = self.focal_length(camera);
+
+        let x_cam = (x - self.width as f32 / 2.0) / f;
+        let y_cam = (self.height as f32 / 2.0 - y) / f;
+
+        (forward + right * x_cam + up * y_cam).normalized()
+    }
+
+    /// Proyecta una esfera: centro en pantalla, radio aparente en píxeles
+    /// (mínimo 2) y profundidad del centro.
+    pub fn project_sphere(
+        &self,
+        center: Vec3,
+        radius: f32,
+        camera: &Camera,
+    ) -> Option<((i32, i32), i32, f32)> {
+        let (screen, z) = self.project_point_depth(center, camera)?;
+        let radius_px = ((radius * self.focal_length(camera) / z) as i32).max(2);
+        Some((screen, radius_px, z))
+    }
+
+    #[allow(dead_code)]
     pub fn world_to_screen_2d(&self, world: Vec2, camera_pos: Vec2, zoom: f32) -> (i32, i32) {
         let sx = (world.x - camera_pos.x) * zoom + (self.width as f32 / 2.0);
         let sy = (world.y - camera_pos.y) * zoom + (self.height as f32 / 2.0);
@@ -87,80 +242,99 @@ impl Renderer {
         (sx as i32, sy as i32)
     }
 
-    /// Dibuja un planeta como DISCO 2D texturizado.
-    /// La textura cubre todo el círculo y se rota en 2D con `rotation`.
+    /// Dibuja un planeta como esfera texturizada de centro `center` y radio
+    /// `radius` (en mundo), con prueba de profundidad por píxel.
+    /// Cada píxel del disco se reconstruye como un punto de la esfera unitaria
+    /// (usando la base de la cámara) y se muestrea la textura equirectangular
+    /// por longitud/latitud. `spin` orienta la superficie (polo y giro).
+    ///
+    /// `light` es la dirección (en mundo, normalizada) desde el cuerpo hacia la
+    /// estrella; con `None` el cuerpo se dibuja sin sombrear (emisivo, p. ej. el Sol).
+    /// Si la superficie tiene mapa nocturno, se mezcla en el lado oscuro con una
+    /// transición suave a lo largo del terminador.
     pub fn draw_textured_sphere(
         &mut self,
-        tex: &Texture,
-        center: (i32, i32),
-        radius: i32,
-        rotation: f32,
+        surface: &SphereSurface,
+        center: Vec3,
+        radius: f32,
+        camera: &Camera,
+        spin: Spin,
+        light: Option<Vec3>,
     ) {
-        if radius <= 0 {
+        let Some(((cx, cy), radius_px, center_z)) = self.project_sphere(center, radius, camera)
+        else {
             return;
-        }
+        };
 
-        let (cx, cy) = center;
-        let r = radius as f32;
-        let r2 = r * r;
+        let r = radius_px as f32;
+        let (forward, right, up) = camera.basis();
 
-        let cos_a = rotation.cos();
-        let sin_a = rotation.sin();
+        // Solo las filas y columnas del disco que caen en pantalla: un cuerpo
+        // grande justo delante del plano cercano mide cientos de miles de píxeles
+        let (cx, cy, rp) = (cx as i64, cy as i64, radius_px as i64);
+        let rows = (-rp).max(-cy)..=rp.min(self.height as i64 - 1 - cy);
+        let cols = (-rp).max(-cx)..=rp.min(self.width as i64 - 1 - cx);
 
-        for py in -radius..=radius {
-            let sy = cy + py;
-            if sy < 0 || sy >= self.height as i32 {
-                continue;
-            }
+        for py in rows {
+            let sy = (cy + py) as i32;
 
-            for px in -radius..=radius {
-                let sx = cx + px;
-                if sx < 0 || sx >= self.width as i32 {
-                    continue;
-                }
+            for px in cols.clone() {
+                let sx = (cx + px) as i32;
 
-                let x = px as f32;
-                let y = py as f32;
-                let dist2 = x * x + y * y;
-                if dist2 > r2 {
+                // Coord. normalizadas [-1, 1] (Y de pantalla hacia abajo)
+                let nx = px as f32 / r;
+                let ny = -(py as f32) / r;
+                let d2 = nx * nx + ny * ny;
+                if d2 > 1.0 {
                     continue; // fuera del círculo
                 }
+                let nz = (1.0 - d2).sqrt();
 
-                // Coord. normalizadas [-1, 1]
-                let nx = x / r;
-                let ny = y / r;
-
-                // Rotar en 2D para que la textura "gire" sobre el planeta
-                let rx = nx * cos_a - ny * sin_a;
-                let ry = nx * sin_a + ny * cos_a;
-
-                // Mapear a [0,1]
-                let u = (rx + 1.0) * 0.5;        // 0..1
-                let v = 1.0 - (ry + 1.0) * 0.5;  // 0..1 (invertimos Y)
+                // Normal en mundo del punto visible de la esfera (mira hacia la cámara)
+                let normal = right * nx + up * ny - forward * nz;
+                let z = center_z - nz * radius;
 
-                if u < 0.0 || u > 1.0 || v < 0.0 || v > 1.0 {
-                    continue;
-                }
+                // Pasar al espacio del cuerpo deshaciendo su orientación
+                let local = spin.to_local(normal);
 
-                let tx = (u * (tex.width as f32 - 1.0)) as usize;
-                let ty = (v * (tex.height as f32 - 1.0)) as usize;
+                let lon = (-local.z).atan2(local.x);
+                let lat = local.y.clamp(-1.0, 1.0).asin();
 
-                if tx >= tex.width || ty >= tex.height {
-                    continue;
-                }
+                let u = 0.5 + lon / (2.0 * PI);
+                let v = 0.5 - lat / PI;
 
-                let color = tex.pixels[ty * tex.width + tx];
+                let mut color = match surface.day {
+                    Some(day) => day.sample(u, v),
+                    None => surface.color,
+                };
                 let a = (color >> 24) & 0xFF;
                 if a < 10 {
                     continue;
                 }
 
-                self.put_pixel(sx, sy, color);
+                // Lambert + ambiente
+                if let Some(l) = light {
+                    let n_dot_l = normal.dot(l);
+                    let diffuse = n_dot_l.max(0.0);
+                    let intensity = self.ambient + (1.0 - self.ambient) * diffuse;
+                    color = color::scale(color, intensity);
+
+                    if let Some(night) = surface.night {
+                        let night_weight = 1.0 - smoothstep(-0.2, 0.05, n_dot_l);
+                        if night_weight > 0.0 {
+                            let lights = color::scale(night.sample(u, v), night_weight);
+                            color = color::add(color, lights);
+                        }
+                    }
+                }
+
+                self.put_pixel_depth(sx, sy, z, color);
             }
         }
     }
 
     /// Blit cuadrado genérico (por si quieres sprites 2D normales, HUD, etc.).
+    #[allow(dead_code)]
     pub fn blit_sprite(&mut self, tex: &Texture, center: (i32, i32), size: i32) {
         if size <= 0 {
             return;
@@ -0,0 +1,84 @@
+use crate::input::InputState;
+use crate::math::Vec3;
+
+/// Aceleración con el motor al 100 % (unidades/s²)
+const MAX_THRUST: f32 = 120.0;
+/// Cuánto cambia la potencia por segundo con Shift/Ctrl
+const THROTTLE_RATE: f32 = 0.5;
+/// Frenado de la asistencia de vuelo (1/s): la velocidad que no va en la
+/// dirección del empuje decae como exp(-k·dt)
+const ASSIST_DAMPING: f32 = 1.5;
+
+/// Modelo de vuelo newtoniano: WASD/QE encienden los propulsores en vez de
+/// mover la cámara directamente, y la velocidad se conserva entre frames.
+pub struct Ship {
+    /// Potencia del motor (0..1)
+    pub throttle: f32,
+    /// Con asistencia la nave frena sola y corrige la deriva lateral
+    pub flight_assist: bool,
+}
+
+impl Ship {
+    pub fn new() -> Self {
+        Self {
+            throttle: 0.5,
+            flight_assist: true,
+        }
+    }
+
+    /// Avanza `velocity` un paso según los propulsores pedidos en `input`.
+    /// `basis` es la base (adelante, derecha, arriba) de la cámara.
+    pub fn update(
+        &mut self,
+        dt: f32,
+        input: &InputState,
+        basis: (Vec3, Vec3, Vec3),
+        velocity: &mut Vec3,
+    ) {
+        if input.subir_potencia {
+            self.throttle += THROTTLE_RATE * dt;
+        }
+        if input.bajar_potencia {
+            self.throttle -= THROTTLE_RATE * dt;
+        }
+        self.throttle = self.throttle.clamp(0.0, 1.0);
+
+        let (forward, right, up) = basis;
+        let mut dir = Vec3::zero();
+        if input.mover_adelante {
+            dir = dir + forward;
+        }
+        if input.mover_atras {
+            dir = dir - forward;
+        }
+        if input.mover_derecha {
+            dir = dir + right;
+        }
+        if input.mover_izquierda {
+            dir = dir - right;
+        }
+        if input.mover_arriba {
+            dir = dir + up;
+        }
+        if input.mover_abajo {
+            dir = dir - up;
+        }
+
+        let thrusting = dir.length() > 0.0;
+        if thrusting {
+            dir = dir.normalized();
+            *velocity = *velocity + dir * (MAX_THRUST * self.throttle * dt);
+        }
+
+        if self.flight_assist {
+            // Se conserva solo la componente a favor del empuje
+            let along = if thrusting {
+                dir * velocity.dot(dir).max(0.0)
+            } else {
+                Vec3::zero()
+            };
+            let drift = *velocity - along;
+            *velocity = along + drift * (-ASSIST_DAMPING * dt).exp();
+        }
+    }
+}
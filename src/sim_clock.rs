@@ -0,0 +1,123 @@
+/// Multiplicadores de tiempo disponibles (tiempo simulado / tiempo real)
+const SCALES: [f32; 12] = [
+    0.1, 0.25, 0.5, 1.0, 2.0, 5.0, 10.0, 100.0, 1_000.0, 10_000.0, 50_000.0, 100_000.0,
+];
+const DEFAULT_SCALE: usize = 3;
+
+/// Duración (en tiempo real) de un paso único con la simulación en pausa
+const STEP_SECONDS: f32 = 1.0 / 60.0;
+
+/// Reloj de la simulación, separado del reloj de los frames: convierte el `dt`
+/// real en `dt` simulado aplicando pausa, multiplicador y sentido del tiempo.
+pub struct SimClock {
+    pub paused: bool,
+    pub reversed: bool,
+    scale_index: usize,
+    step_pending: bool,
+}
+
+impl SimClock {
+    pub fn new() -> Self {
+        Self {
+            paused: false,
+            reversed: false,
+            scale_index: DEFAULT_SCALE,
+            step_pending: false,
+        }
+    }
+
+    /// Multiplicador actual con signo (negativo si el tiempo va hacia atrás)
+    pub fn scale(&self) -> f32 {
+        let s = SCALES[self.scale_index];
+        if self.reversed { -s } else { s }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    pub fn toggle_reverse(&mut self) {
+        self.reversed = !self.reversed;
+    }
+
+    pub fn speed_up(&mut self) {
+        self.scale_index = (self.scale_index + 1).min(SCALES.len() - 1);
+    }
+
+    pub fn slow_down(&mut self) {
+        self.scale_index = self.scale_index.saturating_sub(1);
+    }
+
+    /// Avanza un solo paso en el próximo `advance`; deja la simulación en pausa.
+    pub fn step(&mut self) {
+        self.paused = true;
+        self.step_pending = true;
+    }
+
+    /// Convierte el `dt` real del frame en el `dt` simulado a aplicar
+    pub fn advance(&mut self, frame_dt: f32) -> f32 {
+        if self.step_pending {
+            self.step_pending = false;
+            return STEP_SECONDS * self.scale();
+        }
+        if self.paused {
+            return 0.0;
+        }
+        frame_dt * self.scale()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_in_real_time() {
+        let mut clock = SimClock::new();
+        assert_eq!(clock.scale(), 1.0);
+        assert_eq!(clock.advance(0.5), 0.5);
+    }
+
+    #[test]
+    fn pause_and_reverse() {
+        let mut clock = SimClock::new();
+        clock.toggle_pause();
+        assert_eq!(clock.advance(0.5), 0.0);
+        clock.toggle_pause();
+        clock.toggle_reverse();
+        assert_eq!(clock.advance(0.5), -0.5);
+        clock.toggle_reverse();
+        assert_eq!(clock.advance(0.5), 0.5);
+    }
+
+    #[test]
+    fn single_step_runs_once_and_stays_paused() {
+        let mut clock = SimClock::new();
+        clock.speed_up();
+        clock.step();
+        // El paso no depende del dt del frame, sí del multiplicador
+        assert_eq!(clock.advance(1.0), STEP_SECONDS * 2.0);
+        assert!(clock.paused);
+        assert_eq!(clock.advance(1.0), 0.0);
+
+        clock.toggle_reverse();
+        clock.step();
+        assert_eq!(clock.advance(1.0), -STEP_SECONDS * 2.0);
+    }
+
+    #[test]
+    fn speed_presets_are_clamped() {
+        let mut clock = SimClock::new();
+        for _ in 0..SCALES.len() {
+            clock.speed_up();
+        }
+        assert_eq!(clock.scale(), 100_000.0);
+        assert_eq!(clock.advance(0.01), 1_000.0);
+
+        for _ in 0..SCALES.len() {
+            clock.slow_down();
+        }
+        assert_eq!(clock.scale(), 0.1);
+        assert_eq!(clock.advance(0.5), 0.05);
+    }
+}
@@ -31,7 +31,7 @@ pub fn draw_skybox(renderer: &mut Renderer, camera: &Camera, skybox_texture: &Te
             let u = (theta + PI) / (2.0 * PI);
             let v = (phi + PI / 2.0) / PI;
 
-            let color = sample_texture(skybox_texture, u, v);
+            let color = skybox_texture.sample(u, v);
 
             renderer.put_pixel(x, y, color);
         }
@@ -39,19 +39,7 @@ pub fn draw_skybox(renderer: &mut Renderer, camera: &Camera, skybox_texture: &Te
 }
 
 fn transform_ray_to_world(ray_camera: Vec3, camera: &Camera) -> Vec3 {
-    let forward = camera.forward();
-    let right = forward.cross(Vec3::up()).normalized();
-    let up = right.cross(forward).normalized();
+    let (forward, right, up) = camera.basis();
 
     right * ray_camera.x + up * ray_camera.y - forward * ray_camera.z
 }
-
-fn sample_texture(texture: &Texture, u: f32, v: f32) -> u32 {
-    let u = u.clamp(0.0, 1.0);
-    let v = v.clamp(0.0, 1.0);
-
-    let x = (u * (texture.width - 1) as f32) as usize;
-    let y = (v * (texture.height - 1) as f32) as usize;
-
-    texture.pixels[y * texture.width + x]
-}
\ No newline at end of file
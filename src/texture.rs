@@ -1,5 +1,3 @@
-use image::GenericImageView;
-
 pub struct Texture {
     pub width: usize,
     pub height: usize,
@@ -7,9 +5,8 @@ pub struct Texture {
 }
 
 impl Texture {
-    pub fn from_file(path: &str) -> Self {
-        let img = image::open(path)
-            .unwrap_or_else(|e| panic!("No se pudo cargar {}: {}", path, e));
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let img = image::open(path).map_err(|e| format!("No se pudo cargar {}: {}", path, e))?;
 
         let rgba = img.to_rgba8();
         let (w, h) = rgba.dimensions();
@@ -25,10 +22,22 @@ impl Texture {
             pixels.push(argb);
         }
 
-        Self {
+        Ok(Self {
             width: w as usize,
             height: h as usize,
             pixels,
-        }
+        })
+    }
+
+    /// Muestrea la textura en coordenadas (u, v) en [0, 1].
+    /// `u` se repite (longitud), `v` se limita a los bordes (latitud).
+    pub fn sample(&self, u: f32, v: f32) -> u32 {
+        let u = u - u.floor();
+        let v = v.clamp(0.0, 1.0);
+
+        let x = ((u * self.width as f32) as usize).min(self.width - 1);
+        let y = (v * (self.height - 1) as f32) as usize;
+
+        self.pixels[y * self.width + x]
     }
 }
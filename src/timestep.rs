@@ -0,0 +1,154 @@
+use crate::camera::Camera;
+use crate::math::{Quat, Vec3};
+use crate::world::SolarSystem;
+
+/// Duración de cada paso de actualización (s reales): la simulación da los
+/// mismos resultados sin importar los FPS
+pub const FIXED_DT: f32 = 1.0 / 120.0;
+/// Tope del tiempo de un frame: tras un tirón no se acumulan cientos de pasos
+const MAX_FRAME_TIME: f32 = 0.25;
+
+/// Acumulador del bucle de paso fijo: el tiempo real de cada frame se
+/// reparte en pasos de `FIXED_DT`, y lo que sobra se guarda para el siguiente.
+pub struct FixedTimestep {
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    pub fn new() -> Self {
+        Self { accumulator: 0.0 }
+    }
+
+    /// Suma el tiempo del frame y devuelve cuántos pasos fijos hay que dar
+    pub fn advance(&mut self, frame_dt: f32) -> usize {
+        self.accumulator += frame_dt.clamp(0.0, MAX_FRAME_TIME);
+        let steps = (self.accumulator / FIXED_DT) as usize;
+        self.accumulator -= steps as f32 * FIXED_DT;
+        steps
+    }
+
+    /// Fracción (0..1) del próximo paso ya transcurrida, para interpolar
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / FIXED_DT).clamp(0.0, 1.0)
+    }
+}
+
+/// Lo que se mueve entre pasos: cámara, tiempo de simulación y, con gravedad
+/// mutua, las posiciones de los cuerpos. En modo Kepler basta con el tiempo.
+pub struct Snapshot {
+    camera_position: Vec3,
+    camera_orientation: Quat,
+    time: f64,
+    positions: Option<Vec<Vec3>>,
+}
+
+impl Snapshot {
+    pub fn capture(system: &SolarSystem, camera: &Camera) -> Self {
+        Self {
+            camera_position: camera.position,
+            camera_orientation: camera.orientation,
+            time: system.time,
+            positions: system.nbody.as_ref().map(|n| n.positions.clone()),
+        }
+    }
+
+    /// Estado intermedio entre `self` (t = 0) y `next` (t = 1)
+    pub fn lerp(&self, next: &Snapshot, t: f32) -> Snapshot {
+        let positions = match (&self.positions, &next.positions) {
+            (Some(a), Some(b)) if a.len() == b.len() => {
+                Some(a.iter().zip(b).map(|(&p, &q)| p.lerp(q, t)).collect())
+            }
+            _ => next.positions.clone(),
+        };
+
+        Snapshot {
+            camera_position: self.camera_position.lerp(next.camera_position, t),
+            camera_orientation: self.camera_orientation.slerp(next.camera_orientation, t),
+            time: self.time + (next.time - self.time) * t as f64,
+            positions,
+        }
+    }
+
+    pub fn apply(&self, system: &mut SolarSystem, camera: &mut Camera) {
+        camera.position = self.camera_position;
+        camera.orientation = self.camera_orientation;
+        system.time = self.time;
+        if let (Some(nbody), Some(positions)) = (&mut system.nbody, &self.positions) {
+            nbody.positions.clone_from(positions);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::world::{Body, BodyKind, FreeMotion};
+
+    #[test]
+    fn short_frames_accumulate() {
+        let mut timestep = FixedTimestep::new();
+        assert_eq!(timestep.advance(FIXED_DT * 0.4), 0);
+        assert!((timestep.alpha() - 0.4).abs() < 1e-4);
+        assert_eq!(timestep.advance(FIXED_DT * 0.4), 0);
+        // La tercera completa un paso y deja el resto para el siguiente
+        assert_eq!(timestep.advance(FIXED_DT * 0.4), 1);
+        assert!((timestep.alpha() - 0.2).abs() < 1e-4);
+        assert_eq!(timestep.advance(FIXED_DT * 2.0), 2);
+    }
+
+    #[test]
+    fn long_stalls_are_clamped() {
+        let mut timestep = FixedTimestep::new();
+        let steps = timestep.advance(5.0);
+        assert_eq!(steps, (MAX_FRAME_TIME / FIXED_DT) as usize);
+        assert!(timestep.alpha() < 1.0);
+        // Un dt negativo (reloj que retrocede) no descuenta pasos
+        assert_eq!(timestep.advance(-1.0), 0);
+    }
+
+    #[test]
+    fn lerp_blends_camera_time_and_bodies() {
+        let free = FreeMotion {
+            position: Vec3::zero(),
+            velocity: Vec3::new(1.0, 0.0, 0.0),
+        };
+        let body = Body {
+            name: "Prueba".to_string(),
+            kind: BodyKind::Planet,
+            radius: 1.0,
+            // Sin masa no se pasa al sistema del centro de masas
+            mass: 0.0,
+            color: 0xFFFFFFFF,
+            orbit: None,
+            parent: None,
+            free: Some(free),
+            rotation_period: 0.0,
+            rotation_phase: 0.0,
+            axial_tilt: 0.0,
+            tidally_locked: false,
+            texture: None,
+            night_texture: None,
+        };
+        let mut system = SolarSystem::new(vec![body]);
+        system.start_nbody(crate::world::Integrator::Leapfrog);
+        let mut camera = Camera::new();
+        camera.position = Vec3::zero();
+        camera.orientation = Quat::identity();
+        let before = Snapshot::capture(&system, &camera);
+
+        camera.position = Vec3::new(0.0, 10.0, 0.0);
+        camera.orientation = Quat::from_axis_angle(Vec3::up(), 1.0);
+        system.update(2.0);
+        let after = Snapshot::capture(&system, &camera);
+
+        for (t, expected) in [(0.0, 0.0), (0.5, 1.0), (1.0, 2.0)] {
+            before.lerp(&after, t).apply(&mut system, &mut camera);
+            assert!((system.time - expected).abs() < 1e-6);
+            assert!((camera.position.y - 10.0 * t).abs() < 1e-5);
+            let body = system.body_position(0);
+            assert!((body.x - expected as f32).abs() < 1e-4, "t = {}", t);
+            let angle = camera.orientation.dot(Quat::from_axis_angle(Vec3::up(), t));
+            assert!((angle.abs() - 1.0).abs() < 1e-5);
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use crate::camera::Camera;
+use crate::renderer::Renderer;
+use crate::skybox;
+use crate::texture::Texture;
+use crate::world::SolarSystem;
+
+/// Dibuja un frame completo de la escena (fondo, cuerpos y órbitas) en el
+/// framebuffer del renderer. No depende de ninguna ventana.
+pub fn draw_scene(
+    renderer: &mut Renderer,
+    system: &SolarSystem,
+    camera: &Camera,
+    sky: Option<&Texture>,
+) {
+    renderer.clear(0x000000);
+
+    if let Some(sky) = sky {
+        skybox::draw_skybox(renderer, camera, sky);
+    }
+
+    system.render_bodies(renderer, camera);
+    system.render(renderer, camera);
+}
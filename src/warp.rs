@@ -1,10 +1,23 @@
 use crate::camera::Camera;
-use crate::math::Vec3;
+use crate::math::{smoothstep, Quat, Vec3};
+use crate::world::SolarSystem;
 
+/// Holgura alrededor de un cuerpo que el viaje intenta no atravesar
+const CLEARANCE_PER_RADIUS: f32 = 2.0;
+const CLEARANCE_MARGIN: f32 = 5.0;
+
+/// Viaje animado hacia un cuerpo. El destino se recalcula cada frame con la
+/// posición actual del cuerpo, la trayectoria es una curva de Bézier que rodea
+/// los cuerpos intermedios y la cámara gira para mirar al destino.
 pub struct WarpState {
     pub active: bool,
+    target: usize,
     start: Vec3,
-    target: Vec3,
+    /// Punto de control de la curva, relativo al inicio
+    control: Vec3,
+    /// Posición de llegada relativa al centro del cuerpo destino
+    arrival: Vec3,
+    start_orientation: Quat,
     t: f32,
     duration: f32,
 }
@@ -13,32 +26,129 @@ impl WarpState {
     pub fn new() -> Self {
         Self {
             active: false,
+            target: 0,
             start: Vec3::zero(),
-            target: Vec3::zero(),
+            control: Vec3::zero(),
+            arrival: Vec3::zero(),
+            start_orientation: Quat::identity(),
             t: 0.0,
             duration: 1.5,
         }
     }
 
-    pub fn start_animated(&mut self, start: Vec3, target: Vec3, duration: f32) {
+    /// Empieza un viaje desde la cámara hasta quedar frente a `target`
+    pub fn start_animated(&mut self, camera: &Camera, system: &SolarSystem, target: usize) {
+        let center = system.body_position(target);
+        let arrival = arrival_offset(camera.position, center, system.bodies[target].radius);
+        let end = center + arrival;
+
+        let distance = (end - camera.position).length();
+
         self.active = true;
-        self.start = start;
         self.target = target;
+        self.start = camera.position;
+        self.control = avoidance_control(system, target, camera.position, end) - camera.position;
+        self.arrival = arrival;
+        self.start_orientation = camera.orientation;
         self.t = 0.0;
-        self.duration = duration;
+        self.duration = (1.0 + distance / 150.0).clamp(1.5, 4.0);
     }
 
-    pub fn update(&mut self, dt: f32, camera: &mut Camera) {
+    pub fn update(&mut self, dt: f32, camera: &mut Camera, system: &SolarSystem) {
         if !self.active {
             return;
         }
 
         self.t += dt;
         let alpha = (self.t / self.duration).min(1.0);
-        camera.position = self.start.lerp(self.target, alpha);
+
+        // Aceleración y frenado suaves
+        let s = smoothstep(0.0, 1.0, alpha);
+
+        let center = system.body_position(self.target);
+        let end = center + self.arrival;
+        let control = self.start + self.control;
+        camera.position = bezier(self.start, control, end, s);
+
+        // Gira hacia el destino (slerp) durante la primera parte del viaje;
+        // el giro termina con el horizonte nivelado
+        let facing = Quat::look_rotation(center - camera.position, Vec3::up());
+        let turn = smoothstep(0.0, 0.6, alpha);
+        camera.orientation = self.start_orientation.slerp(facing, turn);
 
         if alpha >= 1.0 {
             self.active = false;
         }
     }
 }
+
+/// Punto de llegada: a unos radios del cuerpo, del lado por el que se llega
+/// y un poco por encima del plano orbital.
+pub fn arrival_offset(from: Vec3, center: Vec3, radius: f32) -> Vec3 {
+    let mut dir = from - center;
+    dir.y = 0.0;
+    let dir = if dir.length() < 1e-3 {
+        Vec3::new(0.0, 0.0, 1.0)
+    } else {
+        dir.normalized()
+    };
+
+    let distance = radius * 4.0 + 10.0;
+    (dir * 0.9 + Vec3::up() * 0.45).normalized() * distance
+}
+
+/// Punto de control para rodear el cuerpo que más se acerca al tramo recto
+/// `start -> end` (sin contar el destino). Si nada estorba, es el punto medio.
+fn avoidance_control(system: &SolarSystem, target: usize, start: Vec3, end: Vec3) -> Vec3 {
+    let mid = start.lerp(end, 0.5);
+    let seg = end - start;
+    let seg_len2 = seg.dot(seg);
+    if seg_len2 < 1e-6 {
+        return mid;
+    }
+
+    let mut worst: Option<(f32, Vec3, Vec3, f32)> = None; // (penetración, punto, centro, holgura)
+    for (i, body) in system.bodies.iter().enumerate() {
+        if i == target {
+            continue;
+        }
+        let center = system.body_position(i);
+        let u = (center - start).dot(seg) / seg_len2;
+        // Los extremos del tramo ya quedan fuera de los cuerpos
+        if !(0.05..=0.95).contains(&u) {
+            continue;
+        }
+
+        let closest = start + seg * u;
+        let dist = (closest - center).length();
+        let clearance = body.radius * CLEARANCE_PER_RADIUS + CLEARANCE_MARGIN;
+        let penetration = clearance - dist;
+        if penetration > 0.0 && worst.is_none_or(|w| penetration > w.0) {
+            worst = Some((penetration, closest, center, clearance));
+        }
+    }
+
+    let Some((_, closest, center, clearance)) = worst else {
+        return mid;
+    };
+
+    let mut away = closest - center;
+    if away.length() < 1e-3 {
+        // El tramo pasa justo por el centro: esquivar por "arriba" del tramo
+        away = seg.cross(Vec3::up()).cross(seg);
+        if away.length() < 1e-3 {
+            away = Vec3::new(1.0, 0.0, 0.0);
+        }
+    }
+
+    // La curva pasa a medio camino entre la cuerda y el control, así que el
+    // control se aleja el doble de la holgura
+    center + away.normalized() * (2.0 * clearance)
+}
+
+fn bezier(p0: Vec3, p1: Vec3, p2: Vec3, t: f32) -> Vec3 {
+    let a = p0.lerp(p1, t);
+    let b = p1.lerp(p2, t);
+    a.lerp(b, t)
+}
+
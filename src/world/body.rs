@@ -1,30 +1,52 @@
+use std::rc::Rc;
+
+use serde::Deserialize;
+
 use crate::math::Vec3;
+use crate::texture::Texture;
 
-#[derive(Clone, Copy)]
+use super::Orbit;
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum BodyKind {
     Star,
     Planet,
     Moon,
 }
 
+/// Estado en la época de un cuerpo libre, en coordenadas del mundo. Sin el
+/// modo N-cuerpos avanza en línea recta.
+#[derive(Clone, Copy)]
+pub struct FreeMotion {
+    pub position: Vec3,
+    pub velocity: Vec3,
+}
+
 pub struct Body {
     pub name: String,
     pub kind: BodyKind,
     pub radius: f32,
+    /// Masa en unidades de escena (con G = 1); solo la usa el modo N-cuerpos.
+    /// 0 es una partícula de prueba que siente la gravedad pero no atrae
+    pub mass: f32,
+    /// Color sólido para cuerpos sin textura
     pub color: u32,
-    pub orbit_radius: f32,
-    pub orbit_speed: f32,
-    pub angle: f32,
-    pub parent: Option<usize>, 
-}
-
-impl Body {
-    pub fn update(&mut self, dt: f32) {
-        match self.kind {
-            BodyKind::Star => {}
-            BodyKind::Planet | BodyKind::Moon => {
-                self.angle += self.orbit_speed * dt;
-            }
-        }
-    }
+    /// Órbita alrededor de `parent` (o del origen si no tiene padre)
+    pub orbit: Option<Orbit>,
+    pub parent: Option<usize>,
+    /// Movimiento de un cuerpo sin órbita ni padre (p. ej. un planeta errante)
+    pub free: Option<FreeMotion>,
+    /// Duración del día sideral en segundos (0 = no gira, negativo = retrógrado)
+    pub rotation_period: f32,
+    /// Ángulo de giro sobre su eje en la época (radianes)
+    pub rotation_phase: f32,
+    /// Inclinación del eje de giro respecto a +Y, hacia +Z (radianes)
+    pub axial_tilt: f32,
+    /// Rotación síncrona con su órbita (ver `Orbit::locked_orientation`); el
+    /// periodo y la inclinación no se usan y `rotation_phase` desplaza la cara
+    pub tidally_locked: bool,
+    pub texture: Option<Rc<Texture>>,
+    /// Mapa emisivo del lado nocturno (luces de ciudades)
+    pub night_texture: Option<Rc<Texture>>,
 }
@@ -0,0 +1,254 @@
+use std::f64::consts::PI;
+
+use crate::math::Vec3;
+
+use super::Orbit;
+
+/// Magnitudes conservadas del sistema en un instante (G = 1, unidades de
+/// escena). Con gravedad mutua deberían mantenerse; cuánto se alejan del
+/// arranque mide el error del integrador y del paso de tiempo.
+#[derive(Clone, Copy, Default)]
+pub struct Diagnostics {
+    pub kinetic: f64,
+    pub potential: f64,
+    pub angular_momentum: [f64; 3],
+}
+
+/// Deriva relativa respecto a un estado de referencia
+#[derive(Clone, Copy)]
+pub struct Drift {
+    pub energy: f64,
+    pub angular_momentum: f64,
+}
+
+impl Diagnostics {
+    pub fn energy(&self) -> f64 {
+        self.kinetic + self.potential
+    }
+
+    pub fn angular_momentum_norm(&self) -> f64 {
+        let [x, y, z] = self.angular_momentum;
+        (x * x + y * y + z * z).sqrt()
+    }
+
+    /// |ΔE / E0| y |ΔL| / |L0| respecto a `baseline`
+    pub fn drift_from(&self, baseline: &Diagnostics) -> Drift {
+        let e0 = baseline.energy();
+        let energy = if e0 != 0.0 {
+            ((self.energy() - e0) / e0).abs()
+        } else {
+            0.0
+        };
+
+        let l0 = baseline.angular_momentum_norm();
+        let dl = [0, 1, 2].map(|k| self.angular_momentum[k] - baseline.angular_momentum[k]);
+        let angular_momentum = if l0 != 0.0 {
+            (dl[0] * dl[0] + dl[1] * dl[1] + dl[2] * dl[2]).sqrt() / l0
+        } else {
+            0.0
+        };
+
+        Drift {
+            energy,
+            angular_momentum,
+        }
+    }
+}
+
+/// Elementos osculantes: la cónica que seguiría el cuerpo alrededor de su
+/// padre si desde este instante solo actuara la gravedad del padre.
+/// Ángulos en radianes, con el mismo plano de referencia que `Orbit`.
+#[derive(Clone, Copy)]
+pub struct OrbitalElements {
+    /// Negativo en órbitas abiertas (e >= 1)
+    pub semi_major_axis: f32,
+    pub eccentricity: f32,
+    pub inclination: f32,
+    pub ascending_node: f32,
+    pub periapsis: f32,
+    pub true_anomaly: f32,
+    /// Periodo en segundos; `None` si la órbita no es cerrada
+    pub period: Option<f32>,
+}
+
+impl OrbitalElements {
+    /// Elementos a partir de la posición y velocidad relativas al padre (en
+    /// el mundo) y del parámetro gravitatorio `mu` = G·(M + m).
+    pub fn from_state(position: Vec3, velocity: Vec3, mu: f32) -> Self {
+        // Mundo -> referencia (X, Y, Z-norte): (x, -z, y)
+        let r = [position.x as f64, -position.z as f64, position.y as f64];
+        let v = [velocity.x as f64, -velocity.z as f64, velocity.y as f64];
+        let mu = mu as f64;
+
+        let r_len = norm(r);
+        let v2 = dot(v, v);
+        let h = cross(r, v);
+        let h_len = norm(h);
+
+        // Vector excentricidad y línea de nodos (Z × h)
+        let vxh = cross(v, h);
+        let e_vec = [0, 1, 2].map(|k| vxh[k] / mu - r[k] / r_len);
+        let e = norm(e_vec);
+        let node = [-h[1], h[0], 0.0];
+        let node_len = norm(node);
+
+        let inclination = (h[2] / h_len).clamp(-1.0, 1.0).acos();
+        let ascending_node = if node_len > 1e-9 {
+            node[1].atan2(node[0]).rem_euclid(2.0 * PI)
+        } else {
+            0.0
+        };
+
+        // Ángulos medidos en el plano orbital desde el nodo (o desde +X si la
+        // órbita está en el plano de referencia)
+        let in_plane = |a: [f64; 3]| -> f64 {
+            let (p, q) = if node_len > 1e-9 {
+                let p = [node[0] / node_len, node[1] / node_len, 0.0];
+                (p, cross([h[0] / h_len, h[1] / h_len, h[2] / h_len], p))
+            } else {
+                let s = h[2].signum();
+                ([1.0, 0.0, 0.0], [0.0, s, 0.0])
+            };
+            dot(a, q).atan2(dot(a, p))
+        };
+
+        let (periapsis, true_anomaly) = if e > 1e-5 {
+            let w = in_plane(e_vec);
+            (w.rem_euclid(2.0 * PI), (in_plane(r) - w).rem_euclid(2.0 * PI))
+        } else {
+            // Circular (hasta el ruido de f32): sin periapsis, la anomalía se cuenta desde el nodo
+            (0.0, in_plane(r).rem_euclid(2.0 * PI))
+        };
+
+        let energy = v2 / 2.0 - mu / r_len;
+        let semi_major_axis = -mu / (2.0 * energy);
+        let period = (e < 1.0 && semi_major_axis > 0.0)
+            .then(|| 2.0 * PI * (semi_major_axis.powi(3) / mu).sqrt());
+
+        Self {
+            semi_major_axis: semi_major_axis as f32,
+            eccentricity: e as f32,
+            inclination: inclination as f32,
+            ascending_node: ascending_node as f32,
+            periapsis: periapsis as f32,
+            true_anomaly: true_anomaly as f32,
+            period: period.map(|p| p as f32),
+        }
+    }
+
+    /// La elipse osculante como `Orbit`, ajustada para que `position_at(time)`
+    /// dé la posición actual. `None` si la órbita no es cerrada.
+    pub fn to_orbit(self, time: f64) -> Option<Orbit> {
+        let period = self.period?;
+        let e = self.eccentricity;
+
+        let half = (self.true_anomaly / 2.0).tan() * ((1.0 - e) / (1.0 + e)).sqrt();
+        let e_anomaly = 2.0 * half.atan();
+        let mean_now = e_anomaly - e * e_anomaly.sin();
+        let mean_motion = 2.0 * std::f32::consts::PI / period;
+
+        Some(Orbit {
+            semi_major_axis: self.semi_major_axis,
+            eccentricity: e,
+            inclination: self.inclination,
+            ascending_node: self.ascending_node,
+            periapsis: self.periapsis,
+            mean_anomaly: (mean_now as f64 - mean_motion as f64 * time)
+                .rem_euclid(2.0 * PI) as f32,
+            mean_motion,
+            node_rate: 0.0,
+            periapsis_rate: 0.0,
+        })
+    }
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn norm(a: [f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn orbit(eccentricity: f32, inclination: f32, ascending_node: f32, periapsis: f32) -> Orbit {
+        Orbit {
+            semi_major_axis: 20.0,
+            eccentricity,
+            inclination,
+            ascending_node,
+            periapsis,
+            mean_anomaly: 0.7,
+            mean_motion: 0.25,
+            node_rate: 0.0,
+            periapsis_rate: 0.0,
+        }
+    }
+
+    fn angle_error(a: f32, b: f32) -> f32 {
+        ((a - b) as f64 + PI).rem_euclid(2.0 * PI) as f32 - PI as f32
+    }
+
+    /// Estado en varios instantes -> elementos -> `Orbit`: los elementos y la
+    /// posición (ahora y más adelante) coinciden con la órbita original
+    fn assert_round_trip(original: Orbit, equatorial: bool) {
+        let mu = original.mean_motion.powi(2) * original.semi_major_axis.powi(3);
+        for time in [0.0, 3.0, 11.0, 20.0] {
+            let position = original.position_at(time);
+            let velocity = original.velocity_at(time, mu);
+            let el = OrbitalElements::from_state(position, velocity, mu);
+
+            assert!((el.semi_major_axis - original.semi_major_axis).abs() < 1e-3);
+            assert!((el.eccentricity - original.eccentricity).abs() < 1e-4);
+            assert!((el.inclination - original.inclination).abs() < 1e-4);
+            if equatorial {
+                // Sin nodo, el periapsis se mide desde +X: cuenta Ω + ω
+                let longitude = original.ascending_node + original.periapsis;
+                assert!(angle_error(el.periapsis, longitude).abs() < 1e-3);
+            } else {
+                assert!(angle_error(el.ascending_node, original.ascending_node).abs() < 1e-4);
+                assert!(angle_error(el.periapsis, original.periapsis).abs() < 1e-3);
+            }
+            let period = 2.0 * std::f32::consts::PI / original.mean_motion;
+            assert!((el.period.unwrap() - period).abs() < 1e-2);
+
+            let fitted = el.to_orbit(time).unwrap();
+            for later in [0.0, 5.0, 13.0] {
+                let t = time + later;
+                let error = (fitted.position_at(t) - original.position_at(t)).length();
+                assert!(error < 1e-2, "t = {}: error {}", t, error);
+            }
+        }
+    }
+
+    #[test]
+    fn eccentric_orbit_round_trips() {
+        assert_round_trip(orbit(0.6, 0.0, 0.0, 1.1), true);
+    }
+
+    #[test]
+    fn inclined_orbit_round_trips() {
+        assert_round_trip(orbit(0.3, 0.8, 1.2, 2.5), false);
+        // Retrógrada
+        assert_round_trip(orbit(0.1, 2.7, 4.0, 0.4), false);
+    }
+
+    #[test]
+    fn open_orbits_have_no_period() {
+        let el =
+            OrbitalElements::from_state(Vec3::new(10.0, 0.0, 0.0), Vec3::new(0.0, 0.0, -2.0), 10.0);
+        assert!(el.eccentricity > 1.0 && el.semi_major_axis < 0.0);
+        assert!(el.period.is_none() && el.to_orbit(0.0).is_none());
+    }
+}
@@ -1,5 +1,15 @@
 pub mod body;
+pub mod diagnostics;
+pub mod nbody;
+pub mod orbit;
+pub mod scale;
+pub mod scene;
 pub mod system;
 
-pub use body::{Body, BodyKind};
+pub use body::{Body, BodyKind, FreeMotion};
+pub use diagnostics::{Diagnostics, Drift, OrbitalElements};
+pub use nbody::{Integrator, NBody};
+pub use orbit::Orbit;
+pub use scale::Scale;
+pub use scene::Scene;
 pub use system::SolarSystem;
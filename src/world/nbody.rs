@@ -0,0 +1,255 @@
+use crate::math::Vec3;
+
+use super::SolarSystem;
+
+/// Constante de gravitación en unidades de escena
+pub const G: f32 = 1.0;
+/// Paso máximo de integración (s de simulación) si ningún cuerpo tiene órbita;
+/// los pasos largos se parten
+const MAX_STEP: f32 = 0.01;
+/// Si no, subpasos por vuelta de la órbita más corta: así la precisión no
+/// depende de las unidades de la escena
+const STEPS_PER_ORBIT: f32 = 300.0;
+/// Tope de subpasos por llamada: con escalas de tiempo muy altas se prefiere
+/// perder precisión a congelar la ventana
+const MAX_SUBSTEPS: usize = 2000;
+
+/// Energía potencial de un par de cuerpos a distancia `r`, coherente con la
+/// fuerza de `NBody`: -G·m·m/r fuera de contacto y el potencial de una esfera
+/// uniforme de radio `contact` (suma de los radios) cuando se solapan.
+pub fn pair_potential(m1: f32, m2: f32, r: f32, contact: f32) -> f64 {
+    let gm = (G * m1 * m2) as f64;
+    let (r, r0) = (r as f64, contact as f64);
+    if r >= r0 {
+        -gm / r
+    } else {
+        -gm * (3.0 * r0 * r0 - r * r) / (2.0 * r0 * r0 * r0)
+    }
+}
+
+/// Método de integración del modo N-cuerpos
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Integrator {
+    /// Kick-drift-kick: simplético, la energía oscila pero no deriva
+    Leapfrog,
+    /// Runge-Kutta de orden 4: más preciso por paso, pero la energía deriva
+    Rk4,
+}
+
+impl Integrator {
+    pub fn name(self) -> &'static str {
+        match self {
+            Integrator::Leapfrog => "leapfrog",
+            Integrator::Rk4 => "rk4",
+        }
+    }
+}
+
+/// Estado del modo de gravedad mutua: cada cuerpo tiene masa, posición y
+/// velocidad (en el mundo) y todos se atraen entre sí.
+pub struct NBody {
+    pub positions: Vec<Vec3>,
+    pub velocities: Vec<Vec3>,
+    pub masses: Vec<f32>,
+    /// Radios, para suavizar la fuerza cuando dos cuerpos se solapan
+    radii: Vec<f32>,
+    pub integrator: Integrator,
+    /// Paso máximo de integración (s de simulación)
+    max_step: f32,
+}
+
+impl NBody {
+    /// Arranca desde el estado actual del sistema en modo Kepler: mismas
+    /// posiciones y velocidades que mantienen la forma de cada órbita con las
+    /// masas reales (ver `SolarSystem::body_velocity`). Se resta la velocidad
+    /// del centro de masas para que el conjunto no se vaya de la escena.
+    pub fn from_system(system: &SolarSystem, integrator: Integrator) -> Self {
+        let n = system.bodies.len();
+        let masses: Vec<f32> = system.bodies.iter().map(|b| b.mass).collect();
+        let radii = system.bodies.iter().map(|b| b.radius).collect();
+        let max_step = system
+            .bodies
+            .iter()
+            .filter_map(|b| b.orbit.as_ref())
+            .map(|o| 2.0 * std::f32::consts::PI / o.mean_motion.abs() / STEPS_PER_ORBIT)
+            .min_by(f32::total_cmp)
+            .unwrap_or(MAX_STEP);
+        let positions = (0..n).map(|i| system.body_position(i)).collect();
+        let mut velocities: Vec<Vec3> = (0..n).map(|i| system.body_velocity(i)).collect();
+
+        let total: f32 = masses.iter().sum();
+        if total > 0.0 {
+            let momentum = velocities
+                .iter()
+                .zip(&masses)
+                .fold(Vec3::zero(), |acc, (&v, &m)| acc + v * m);
+            let drift = momentum / total;
+            for v in &mut velocities {
+                *v = *v - drift;
+            }
+        }
+
+        Self {
+            positions,
+            velocities,
+            masses,
+            radii,
+            integrator,
+            max_step,
+        }
+    }
+
+    /// El `dt` más largo que `step` integra sin llegar al tope de subpasos
+    pub fn max_interval(&self) -> f32 {
+        self.max_step * MAX_SUBSTEPS as f32
+    }
+
+    /// Avanza `dt` segundos (negativo = hacia atrás) en subpasos de como mucho `max_step`
+    pub fn step(&mut self, dt: f32) {
+        if dt == 0.0 {
+            return;
+        }
+        let steps = ((dt.abs() / self.max_step).ceil() as usize).clamp(1, MAX_SUBSTEPS);
+        let h = dt / steps as f32;
+
+        for _ in 0..steps {
+            match self.integrator {
+                Integrator::Leapfrog => self.leapfrog(h),
+                Integrator::Rk4 => self.rk4(h),
+            }
+        }
+    }
+
+    /// Aceleración gravitatoria de cada cuerpo con los cuerpos en `positions`.
+    /// Los cuerpos no chocan: si se solapan, la fuerza deja de crecer (como
+    /// dentro de una esfera uniforme) para que un cruce no los lance fuera
+    /// del sistema.
+    fn accelerations(&self, positions: &[Vec3]) -> Vec<Vec3> {
+        let mut acc = vec![Vec3::zero(); positions.len()];
+
+        for i in 0..positions.len() {
+            for j in (i + 1)..positions.len() {
+                let d = positions[j] - positions[i];
+                let r = d.length().max(self.radii[i] + self.radii[j]);
+                let inv_r3 = 1.0 / (r * r * r);
+                acc[i] = acc[i] + d * (G * self.masses[j] * inv_r3);
+                acc[j] = acc[j] - d * (G * self.masses[i] * inv_r3);
+            }
+        }
+        acc
+    }
+
+    fn leapfrog(&mut self, h: f32) {
+        let acc = self.accelerations(&self.positions);
+        for ((x, v), a) in self.positions.iter_mut().zip(&mut self.velocities).zip(acc) {
+            *v = *v + a * (h * 0.5);
+            *x = *x + *v * h;
+        }
+
+        let acc = self.accelerations(&self.positions);
+        for (v, a) in self.velocities.iter_mut().zip(acc) {
+            *v = *v + a * (h * 0.5);
+        }
+    }
+
+    fn rk4(&mut self, h: f32) {
+        let x0 = self.positions.clone();
+        let v0 = self.velocities.clone();
+
+        let a1 = self.accelerations(&x0);
+        let x2 = offset(&x0, &v0, h * 0.5);
+        let v2 = offset(&v0, &a1, h * 0.5);
+        let a2 = self.accelerations(&x2);
+        let x3 = offset(&x0, &v2, h * 0.5);
+        let v3 = offset(&v0, &a2, h * 0.5);
+        let a3 = self.accelerations(&x3);
+        let x4 = offset(&x0, &v3, h);
+        let v4 = offset(&v0, &a3, h);
+        let a4 = self.accelerations(&x4);
+
+        for i in 0..x0.len() {
+            let dx = v0[i] + (v2[i] + v3[i]) * 2.0 + v4[i];
+            let dv = a1[i] + (a2[i] + a3[i]) * 2.0 + a4[i];
+            self.positions[i] = x0[i] + dx * (h / 6.0);
+            self.velocities[i] = v0[i] + dv * (h / 6.0);
+        }
+    }
+}
+
+/// `base + rate * h`, elemento a elemento
+fn offset(base: &[Vec3], rate: &[Vec3], h: f32) -> Vec<Vec3> {
+    base.iter().zip(rate).map(|(&b, &r)| b + r * h).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const M: f32 = 1000.0;
+    const R: f32 = 10.0;
+
+    /// Estrella y planeta en órbita circular alrededor del centro de masas
+    fn two_body(integrator: Integrator) -> (NBody, f32) {
+        let m = 1.0;
+        let total = M + m;
+        let speed = (G * total / R).sqrt();
+        let period = 2.0 * std::f32::consts::PI * (R * R * R / (G * total)).sqrt();
+        let nbody = NBody {
+            positions: vec![
+                Vec3::new(-R * m / total, 0.0, 0.0),
+                Vec3::new(R * M / total, 0.0, 0.0),
+            ],
+            velocities: vec![
+                Vec3::new(0.0, 0.0, speed * m / total),
+                Vec3::new(0.0, 0.0, -speed * M / total),
+            ],
+            masses: vec![M, m],
+            radii: vec![1.0, 0.5],
+            integrator,
+            max_step: period / STEPS_PER_ORBIT,
+        };
+        (nbody, period)
+    }
+
+    fn energy(nbody: &NBody) -> f64 {
+        let kinetic: f64 = nbody
+            .velocities
+            .iter()
+            .zip(&nbody.masses)
+            .map(|(v, &m)| 0.5 * m as f64 * v.dot(*v) as f64)
+            .sum();
+        let r = (nbody.positions[1] - nbody.positions[0]).length();
+        kinetic + pair_potential(nbody.masses[0], nbody.masses[1], r, 1.5)
+    }
+
+    #[test]
+    fn circular_orbit_keeps_its_energy() {
+        for integrator in [Integrator::Leapfrog, Integrator::Rk4] {
+            let (mut nbody, period) = two_body(integrator);
+            let e0 = energy(&nbody);
+            for _ in 0..50 {
+                nbody.step(period);
+                let drift = ((energy(&nbody) - e0) / e0).abs();
+                assert!(drift < 1e-4, "{}: deriva {}", integrator.name(), drift);
+            }
+            // Sigue en órbita, a la misma distancia
+            let r = (nbody.positions[1] - nbody.positions[0]).length();
+            assert!((r - R).abs() < 1e-2, "{}: r = {}", integrator.name(), r);
+        }
+    }
+
+    #[test]
+    fn leapfrog_runs_backwards_to_the_start() {
+        let (mut nbody, period) = two_body(Integrator::Leapfrog);
+        let (positions, velocities) = (nbody.positions.clone(), nbody.velocities.clone());
+
+        nbody.step(3.5 * period);
+        assert!((nbody.positions[1] - positions[1]).length() > 1.0);
+        nbody.step(-3.5 * period);
+
+        for i in 0..2 {
+            assert!((nbody.positions[i] - positions[i]).length() < 1e-3);
+            assert!((nbody.velocities[i] - velocities[i]).length() < 1e-3);
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use std::f32::consts::PI;
+
+use crate::math::{Quat, Vec3};
+
+/// Elementos keplerianos de una órbita alrededor del cuerpo padre.
+/// Ángulos en radianes, `mean_motion` y las tasas en radianes por segundo.
+///
+/// El plano de referencia es el XZ del mundo y su normal (+Y) es el "norte":
+/// con todos los ángulos en cero el cuerpo parte de +X y avanza hacia -Z.
+#[derive(Clone, Copy)]
+pub struct Orbit {
+    pub semi_major_axis: f32,
+    pub eccentricity: f32,
+    pub inclination: f32,
+    /// Longitud del nodo ascendente (Ω)
+    pub ascending_node: f32,
+    /// Argumento del periapsis (ω)
+    pub periapsis: f32,
+    /// Anomalía media en la época (t = 0)
+    pub mean_anomaly: f32,
+    pub mean_motion: f32,
+    /// Precesión del nodo (dΩ/dt); en la época vale `ascending_node`
+    pub node_rate: f32,
+    /// Precesión del periapsis (dω/dt)
+    pub periapsis_rate: f32,
+}
+
+impl Orbit {
+    /// La órbita con el nodo y el periapsis girados hasta el tiempo `time`
+    pub fn at(&self, time: f64) -> Orbit {
+        let turn = |angle: f32, rate: f32| {
+            (angle as f64 + rate as f64 * time).rem_euclid(2.0 * std::f64::consts::PI) as f32
+        };
+        Orbit {
+            ascending_node: turn(self.ascending_node, self.node_rate),
+            periapsis: turn(self.periapsis, self.periapsis_rate),
+            ..*self
+        }
+    }
+
+    /// Anomalía media en el tiempo `time` (segundos desde la época), en [0, 2π)
+    pub fn mean_anomaly_at(&self, time: f64) -> f32 {
+        let m = self.mean_anomaly as f64 + self.mean_motion as f64 * time;
+        m.rem_euclid(2.0 * std::f64::consts::PI) as f32
+    }
+
+    /// Posición relativa al padre en el tiempo `time`
+    pub fn position_at(&self, time: f64) -> Vec3 {
+        let e_anomaly = solve_kepler(self.mean_anomaly_at(time), self.eccentricity);
+        self.at(time).position_at_eccentric_anomaly(e_anomaly)
+    }
+
+    /// Velocidad relativa al padre en el tiempo `time` si el padre tuviera el
+    /// parámetro gravitatorio `mu` (G·M): tangente a la elipse en el punto
+    /// actual y con la rapidez de la ecuación vis-viva. Con un `mu` distinto de
+    /// n²a³ se conserva la forma de la órbita pero no su periodo.
+    pub fn velocity_at(&self, time: f64, mu: f32) -> Vec3 {
+        let a = self.semi_major_axis;
+        let e = self.eccentricity;
+        let e_anomaly = solve_kepler(self.mean_anomaly_at(time), e);
+
+        // Derivada de la posición respecto a E: apunta en el sentido de avance
+        let (s, c) = e_anomaly.sin_cos();
+        let now = self.at(time);
+        let tangent = now.orbital_to_world(-a * s, a * (1.0 - e * e).sqrt() * c);
+
+        let r = now.position_at_eccentric_anomaly(e_anomaly).length();
+        let speed = (mu * (2.0 / r - 1.0 / a)).max(0.0).sqrt();
+        tangent.normalized() * speed * self.mean_motion.signum()
+    }
+
+    /// Orientación de un cuerpo en rotación síncrona en el tiempo `time`: el
+    /// polo sobre la normal de la órbita y la longitud 0 hacia el padre según
+    /// la longitud media Ω+ω+M (con π para mirar hacia dentro). Gira con la
+    /// velocidad media más la precesión del nodo y del periapsis; en una órbita
+    /// excéntrica el padre oscila alrededor de la longitud 0 (libración).
+    pub fn locked_orientation(&self, time: f64) -> Quat {
+        let now = self.at(time);
+        let (s, c) = self.mean_anomaly_at(time).sin_cos();
+        let outward = now.orbital_to_world(c, s);
+        let ahead = now.orbital_to_world(-s, c);
+        let pole = outward.cross(ahead);
+
+        // Ejes del cuerpo: +X hacia el padre, +Y el polo, -Z = +X × +Y
+        Quat::look_rotation(outward.cross(pole), pole)
+    }
+
+    /// Posición relativa al padre para una anomalía excéntrica dada, con el
+    /// nodo y el periapsis de la época (ver `at`).
+    /// Recorrer E en [0, 2π] traza la elipse completa.
+    pub fn position_at_eccentric_anomaly(&self, e_anomaly: f32) -> Vec3 {
+        let a = self.semi_major_axis;
+        let e = self.eccentricity;
+
+        // Coordenadas en el plano orbital (periapsis sobre +x)
+        let x = a * (e_anomaly.cos() - e);
+        let y = a * (1.0 - e * e).sqrt() * e_anomaly.sin();
+
+        self.orbital_to_world(x, y)
+    }
+
+    /// Rota un punto del plano orbital por ω, i y Ω y lo lleva a ejes del mundo
+    fn orbital_to_world(&self, x: f32, y: f32) -> Vec3 {
+        let (sw, cw) = self.periapsis.sin_cos();
+        let (si, ci) = self.inclination.sin_cos();
+        let (so, co) = self.ascending_node.sin_cos();
+
+        let rx = x * (co * cw - so * sw * ci) - y * (co * sw + so * cw * ci);
+        let ry = x * (so * cw + co * sw * ci) - y * (so * sw - co * cw * ci);
+        let rz = x * (sw * si) + y * (cw * si);
+
+        // Referencia (X, Y, Z-norte) -> mundo (x, -z, y), una rotación propia
+        Vec3::new(rx, rz, -ry)
+    }
+}
+
+/// Resuelve la ecuación de Kepler M = E - e·sin(E) por Newton-Raphson.
+pub fn solve_kepler(mean_anomaly: f32, eccentricity: f32) -> f32 {
+    let m = mean_anomaly;
+    let e = eccentricity;
+
+    let mut e_anomaly = if e < 0.8 { m } else { PI };
+    for _ in 0..12 {
+        let f = e_anomaly - e * e_anomaly.sin() - m;
+        let df = 1.0 - e * e_anomaly.cos();
+        let step = f / df;
+        e_anomaly -= step;
+        if step.abs() < 1e-6 {
+            break;
+        }
+    }
+    e_anomaly
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kepler_converges_for_high_eccentricity() {
+        for e in [0.0, 0.5, 0.9, 0.97] {
+            for i in 0..=64 {
+                let m = i as f32 / 64.0 * 2.0 * PI;
+                let e_anomaly = solve_kepler(m, e);
+                let residual = e_anomaly - e * e_anomaly.sin() - m;
+                assert!(
+                    residual.abs() < 1e-5,
+                    "e = {}, M = {}: residuo {}",
+                    e,
+                    m,
+                    residual
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn position_is_periodic_and_starts_at_periapsis() {
+        let orbit = Orbit {
+            semi_major_axis: 10.0,
+            eccentricity: 0.9,
+            inclination: 0.3,
+            ascending_node: 1.0,
+            periapsis: 2.0,
+            mean_anomaly: 0.0,
+            mean_motion: 0.5,
+            node_rate: 0.0,
+            periapsis_rate: 0.0,
+        };
+        let period = 2.0 * std::f64::consts::PI / 0.5;
+
+        // En el periapsis la distancia es a(1 - e)
+        assert!((orbit.position_at(0.0).length() - 1.0).abs() < 1e-4);
+        let a = orbit.position_at(3.0);
+        let b = orbit.position_at(3.0 + period);
+        assert!((a - b).length() < 1e-3);
+    }
+
+    /// Ángulo entre la longitud 0 de un cuerpo sincrónico y la dirección al padre
+    fn locked_error(orbit: &Orbit, time: f64) -> f32 {
+        let q = orbit.locked_orientation(time);
+        let to_parent = orbit.position_at(time).normalized() * -1.0;
+        q.rotate(Vec3::new(1.0, 0.0, 0.0)).dot(to_parent).clamp(-1.0, 1.0).acos()
+    }
+
+    #[test]
+    fn locked_body_faces_parent() {
+        let orbit = Orbit {
+            semi_major_axis: 10.0,
+            eccentricity: 0.0,
+            inclination: 0.5,
+            ascending_node: 1.0,
+            periapsis: 2.0,
+            mean_anomaly: 0.3,
+            mean_motion: 0.5,
+            node_rate: -0.01,
+            periapsis_rate: 0.03,
+        };
+        for time in [0.0, 1.0, 7.5, 40.0, 333.0] {
+            assert!(locked_error(&orbit, time) < 1e-3, "t = {}", time);
+
+            // El polo es la normal de la órbita (precesada)
+            let pole = orbit.locked_orientation(time).rotate(Vec3::up());
+            let now = orbit.at(time);
+            let normal = now.orbital_to_world(1.0, 0.0).cross(now.orbital_to_world(0.0, 1.0));
+            assert!((pole - normal).length() < 1e-4, "t = {}", time);
+        }
+
+        // Con excentricidad el padre oscila alrededor de la longitud 0 (~2e)
+        let eccentric = Orbit { eccentricity: 0.1, ..orbit };
+        for time in [0.0, 1.0, 7.5, 40.0, 333.0] {
+            assert!(locked_error(&eccentric, time) < 0.21, "t = {}", time);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::math::Vec3;
+
+/// Kilómetros en una unidad astronómica
+pub const AU_KM: f64 = 149_597_870.7;
+/// Constante de gravitación en km³/(kg·s²)
+const G_KM: f64 = 6.674_30e-20;
+
+/// Escala real: kilómetros por unidad de escena
+const KM_PER_UNIT: f64 = 1000.0;
+/// Escala comprimida: unidades de escena a 1 UA del Sol; la distancia crece
+/// con la raíz cuadrada de la real
+const COMPRESSED_AU: f64 = 80.0;
+/// Escala comprimida: radio en unidades = este factor · √(radio en km)
+const COMPRESSED_RADIUS: f64 = 0.02;
+
+/// Cómo se llevan las distancias de una escena en unidades reales (km) a
+/// unidades de escena.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Scale {
+    /// Proporciones reales (1 unidad = 1000 km): los planetas son puntos
+    /// perdidos en el vacío, como en la realidad
+    True,
+    /// Distancias y radios comprimidos con raíces cuadradas para que todo
+    /// quepa en pantalla; las lunas se alejan de su planeta en proporción a
+    /// su radio agrandado. Los periodos no cambian.
+    Compressed,
+}
+
+impl Scale {
+    /// Radio de un cuerpo
+    pub fn radius(self, km: f64) -> f32 {
+        match self {
+            Scale::True => (km / KM_PER_UNIT) as f32,
+            Scale::Compressed => (COMPRESSED_RADIUS * km.sqrt()) as f32,
+        }
+    }
+
+    /// Semieje mayor de una órbita; `planet_radius` es el radio (km) del
+    /// padre si es un planeta, `None` si se orbita una estrella
+    pub fn semi_major_axis(self, km: f64, planet_radius: Option<f64>) -> f32 {
+        match (self, planet_radius) {
+            (Scale::True, _) => (km / KM_PER_UNIT) as f32,
+            (Scale::Compressed, None) => (COMPRESSED_AU * (km / AU_KM).sqrt()) as f32,
+            (Scale::Compressed, Some(r)) => self.radius(r) * (km / r).sqrt() as f32,
+        }
+    }
+
+    /// Punto del mundo (km desde el origen), comprimido como una órbita solar
+    pub fn point(self, km: [f64; 3]) -> Vec3 {
+        let dist = (km[0] * km[0] + km[1] * km[1] + km[2] * km[2]).sqrt();
+        let factor = if dist > 0.0 {
+            self.semi_major_axis(dist, None) as f64 / dist
+        } else {
+            0.0
+        };
+        Vec3::new(
+            (km[0] * factor) as f32,
+            (km[1] * factor) as f32,
+            (km[2] * factor) as f32,
+        )
+    }
+
+    /// Masa (kg) en unidades de escena con G = 1. Con la escala comprimida
+    /// no hay masa que respete las distancias y los periodos a la vez: `None`
+    /// deja que la escena la deduzca de las órbitas.
+    pub fn mass(self, kg: f64) -> Option<f32> {
+        match self {
+            Scale::True => Some((G_KM * kg / KM_PER_UNIT.powi(3)) as f32),
+            Scale::Compressed => None,
+        }
+    }
+}
@@ -0,0 +1,387 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use serde::Deserialize;
+
+use crate::calendar;
+use crate::texture::Texture;
+
+use crate::math::Vec3;
+
+use super::nbody::G;
+use super::{Body, BodyKind, FreeMotion, Orbit, Scale, SolarSystem};
+
+/// Escena cargada desde archivo: el sistema y el fondo de estrellas.
+pub struct Scene {
+    pub system: SolarSystem,
+    pub skybox: Option<Texture>,
+    /// Posición inicial de la cámara (mirando al origen), si la escena la elige
+    pub camera: Option<Vec3>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneFile {
+    skybox: Option<String>,
+    #[serde(default)]
+    units: UnitsDef,
+    /// Fecha UTC de los elementos orbitales (t = 0), p. ej. "2000-01-01T12:00";
+    /// por defecto J2000
+    epoch: Option<String>,
+    /// Posición inicial de la cámara [x, y, z], en las unidades del archivo
+    camera: Option<[f64; 3]>,
+    bodies: Vec<BodyDef>,
+}
+
+/// Unidades de las magnitudes del archivo
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum UnitsDef {
+    /// Unidades de escena y segundos (G = 1)
+    #[default]
+    Scene,
+    /// Kilómetros, días y kilogramos; se llevan a la escena según la `Scale`
+    Real,
+}
+
+/// Conversión de las magnitudes del archivo a unidades de escena
+#[derive(Clone, Copy)]
+enum Units {
+    Scene,
+    Real(Scale),
+}
+
+impl Units {
+    /// Segundos por unidad de tiempo del archivo
+    fn time(self) -> f32 {
+        match self {
+            Units::Scene => 1.0,
+            Units::Real(_) => 86_400.0,
+        }
+    }
+
+    fn radius(self, r: f64) -> f32 {
+        match self {
+            Units::Scene => r as f32,
+            Units::Real(scale) => scale.radius(r),
+        }
+    }
+
+    /// `planet_radius`: radio del padre en el archivo si es un planeta o una luna
+    fn semi_major_axis(self, a: f64, planet_radius: Option<f64>) -> f32 {
+        match self {
+            Units::Scene => a as f32,
+            Units::Real(scale) => scale.semi_major_axis(a, planet_radius),
+        }
+    }
+
+    /// `None` si la escala no admite masas reales (ver `Scale::mass`)
+    fn mass(self, m: f64) -> Option<f32> {
+        match self {
+            Units::Scene => Some(m as f32),
+            Units::Real(scale) => scale.mass(m),
+        }
+    }
+
+    fn point(self, p: [f64; 3]) -> Vec3 {
+        match self {
+            Units::Scene => Vec3::new(p[0] as f32, p[1] as f32, p[2] as f32),
+            Units::Real(scale) => scale.point(p),
+        }
+    }
+}
+
+/// Un cuerpo tal como aparece en el archivo. Ángulos en grados.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BodyDef {
+    name: String,
+    kind: BodyKind,
+    parent: Option<String>,
+    radius: f64,
+    /// Masa con G = 1 (modo N-cuerpos; en kg con unidades reales); ver `default_masses`
+    mass: Option<f64>,
+    color: u32,
+    orbit: Option<OrbitDef>,
+    /// Cuerpo libre, sin padre ni órbita: posición [x, y, z] en el mundo (+Y arriba)
+    position: Option<[f32; 3]>,
+    /// Velocidad [x, y, z] del cuerpo libre, en unidades por segundo
+    velocity: Option<[f32; 3]>,
+    /// Segundos (días con unidades reales) por vuelta sobre su eje (negativo = retrógrado)
+    rotation_period: Option<f32>,
+    /// Grados
+    #[serde(default)]
+    rotation_phase: f32,
+    /// Grados
+    #[serde(default)]
+    axial_tilt: f32,
+    /// Gira con el mismo periodo que su órbita mostrando siempre la misma cara al padre
+    #[serde(default)]
+    tidally_locked: bool,
+    texture: Option<String>,
+    night_texture: Option<String>,
+}
+
+/// Elementos keplerianos en el archivo. Ángulos en grados, `mean_motion` y
+/// las tasas de precesión en grados por segundo (por día con unidades reales).
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OrbitDef {
+    semi_major_axis: f64,
+    #[serde(default)]
+    eccentricity: f32,
+    #[serde(default)]
+    inclination: f32,
+    #[serde(default)]
+    ascending_node: f32,
+    #[serde(default)]
+    periapsis: f32,
+    #[serde(default)]
+    mean_anomaly: f32,
+    mean_motion: f32,
+    #[serde(default)]
+    node_rate: f32,
+    #[serde(default)]
+    periapsis_rate: f32,
+}
+
+impl OrbitDef {
+    fn to_orbit(
+        &self,
+        body: &str,
+        units: Units,
+        planet_radius: Option<f64>,
+    ) -> Result<Orbit, String> {
+        if !(0.0..1.0).contains(&self.eccentricity) {
+            return Err(format!(
+                "{}: la excentricidad debe estar en [0, 1) (es {})",
+                body, self.eccentricity
+            ));
+        }
+
+        let rate = |deg: f32| deg.to_radians() / units.time();
+        Ok(Orbit {
+            semi_major_axis: units.semi_major_axis(self.semi_major_axis, planet_radius),
+            eccentricity: self.eccentricity,
+            inclination: self.inclination.to_radians(),
+            ascending_node: self.ascending_node.to_radians(),
+            periapsis: self.periapsis.to_radians(),
+            mean_anomaly: self.mean_anomaly.to_radians(),
+            mean_motion: rate(self.mean_motion),
+            node_rate: rate(self.node_rate),
+            periapsis_rate: rate(self.periapsis_rate),
+        })
+    }
+}
+
+impl Scene {
+    /// Carga una escena TOML (ver `scenes/demo.toml`). `scale` solo se aplica
+    /// a las escenas en unidades reales (ver `scenes/sistema_solar.toml`).
+    pub fn load(path: &str, scale: Scale) -> Result<Scene, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("No se pudo leer {}: {}", path, e))?;
+        let file: SceneFile =
+            toml::from_str(&text).map_err(|e| format!("Escena inválida {}: {}", path, e))?;
+
+        let units = match file.units {
+            UnitsDef::Scene => Units::Scene,
+            UnitsDef::Real => Units::Real(scale),
+        };
+        let epoch = file
+            .epoch
+            .as_deref()
+            .map(calendar::parse_date)
+            .transpose()
+            .map_err(|e| format!("{}: epoch: {}", path, e))?;
+
+        let mut textures = TextureCache::default();
+        let mut indices: HashMap<String, usize> = HashMap::new();
+        let mut bodies: Vec<Body> = Vec::with_capacity(file.bodies.len());
+        // Radios tal como están en el archivo, para escalar las órbitas de las lunas
+        let mut file_radii = Vec::with_capacity(file.bodies.len());
+
+        for def in file.bodies {
+            if indices.contains_key(&def.name) {
+                return Err(format!("Cuerpo duplicado: {}", def.name));
+            }
+
+            // El padre debe estar definido antes, así no puede haber ciclos
+            let parent = match &def.parent {
+                None => None,
+                Some(name) => Some(*indices.get(name).ok_or_else(|| {
+                    format!("{}: el padre {} no está definido antes", def.name, name)
+                })?),
+            };
+
+            let texture = def
+                .texture
+                .as_deref()
+                .map(|p| textures.get(p))
+                .transpose()?;
+            let night_texture = def
+                .night_texture
+                .as_deref()
+                .map(|p| textures.get(p))
+                .transpose()?;
+
+            // Las lunas escalan su órbita con el radio del planeta; los planetas, con la UA
+            let planet_radius = parent
+                .filter(|&p| !matches!(bodies[p].kind, BodyKind::Star))
+                .map(|p| file_radii[p]);
+            let orbit = def
+                .orbit
+                .as_ref()
+                .map(|o| o.to_orbit(&def.name, units, planet_radius))
+                .transpose()?;
+
+            let free = match (def.position, def.velocity) {
+                (None, None) => None,
+                (Some(_), _) if matches!(units, Units::Real(_)) => {
+                    return Err(format!(
+                        "{}: position y velocity no se admiten con unidades reales",
+                        def.name
+                    ));
+                }
+                (None, Some(_)) => {
+                    return Err(format!("{}: velocity requiere position", def.name));
+                }
+                (Some(_), _) if orbit.is_some() || parent.is_some() => {
+                    return Err(format!(
+                        "{}: un cuerpo con position no puede tener órbita ni padre",
+                        def.name
+                    ));
+                }
+                (Some(p), v) => {
+                    let v = v.unwrap_or([0.0; 3]);
+                    Some(FreeMotion {
+                        position: Vec3::new(p[0], p[1], p[2]),
+                        velocity: Vec3::new(v[0], v[1], v[2]),
+                    })
+                }
+            };
+            if def.mass.is_some_and(|m| m < 0.0) {
+                return Err(format!("{}: la masa no puede ser negativa", def.name));
+            }
+
+            if def.tidally_locked {
+                if orbit.is_none() {
+                    return Err(format!("{}: tidally_locked requiere una órbita", def.name));
+                }
+                if def.rotation_period.is_some() || def.axial_tilt != 0.0 {
+                    return Err(format!(
+                        "{}: tidally_locked es incompatible con rotation_period y axial_tilt",
+                        def.name
+                    ));
+                }
+            }
+
+            indices.insert(def.name.clone(), bodies.len());
+            file_radii.push(def.radius);
+            bodies.push(Body {
+                name: def.name,
+                kind: def.kind,
+                radius: units.radius(def.radius),
+                mass: def.mass.and_then(|m| units.mass(m)).unwrap_or(f32::NAN),
+                color: def.color,
+                orbit,
+                parent,
+                free,
+                rotation_period: def.rotation_period.unwrap_or(0.0) * units.time(),
+                rotation_phase: def.rotation_phase.to_radians(),
+                axial_tilt: def.axial_tilt.to_radians(),
+                tidally_locked: def.tidally_locked,
+                texture,
+                night_texture,
+            });
+        }
+
+        default_masses(&mut bodies);
+
+        let skybox = file.skybox.as_deref().map(Texture::from_file).transpose()?;
+
+        let mut system = SolarSystem::new(bodies);
+        system.epoch = epoch.unwrap_or(calendar::J2000);
+
+        Ok(Scene {
+            system,
+            skybox,
+            camera: file.camera.map(|p| units.point(p)),
+        })
+    }
+}
+
+/// Los cuerpos sin `mass` (marcados con NaN al cargar) toman la masa que
+/// mantiene el periodo de su satélite más cercano (G·M = n²a³); sin satélites
+/// quedan como partículas de prueba con masa 0.
+fn default_masses(bodies: &mut [Body]) {
+    for i in 0..bodies.len() {
+        if !bodies[i].mass.is_nan() {
+            continue;
+        }
+        let innermost = bodies
+            .iter()
+            .filter(|b| b.parent == Some(i))
+            .filter_map(|b| b.orbit.as_ref())
+            .min_by(|a, b| a.semi_major_axis.total_cmp(&b.semi_major_axis));
+        bodies[i].mass = match innermost {
+            Some(o) => o.mean_motion * o.mean_motion * o.semi_major_axis.powi(3) / G,
+            None => 0.0,
+        };
+    }
+}
+
+/// Evita cargar dos veces la misma imagen si varios cuerpos la comparten.
+#[derive(Default)]
+struct TextureCache {
+    loaded: HashMap<String, Rc<Texture>>,
+}
+
+impl TextureCache {
+    fn get(&mut self, path: &str) -> Result<Rc<Texture>, String> {
+        if let Some(tex) = self.loaded.get(path) {
+            return Ok(tex.clone());
+        }
+        let tex = Rc::new(Texture::from_file(path)?);
+        self.loaded.insert(path.to_string(), tex.clone());
+        Ok(tex)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preset_moons_face_their_planet() {
+        let mut system = Scene::load("scenes/sistema_solar.toml", Scale::Compressed)
+            .unwrap()
+            .system;
+        let locked: Vec<usize> = (0..system.bodies.len())
+            .filter(|&i| system.bodies[i].tidally_locked)
+            .collect();
+        assert!(locked.len() >= 9);
+
+        // Hasta ~30 años: el nodo de la Luna da más de una vuelta
+        for days in [0.0, 0.4, 17.0, 1234.5, 6800.0, 11_000.0] {
+            system.time = days * 86_400.0;
+            for &i in &locked {
+                let body = &system.bodies[i];
+                let orbit = body.orbit.unwrap();
+                let parent = body.parent.unwrap();
+                let spin = system.body_spin(i).orientation;
+
+                let to_parent =
+                    (system.body_position(parent) - system.body_position(i)).normalized();
+                let facing = spin.rotate(Vec3::new(1.0, 0.0, 0.0)).dot(to_parent);
+                let facing = facing.clamp(-1.0, 1.0).acos();
+                // Libración: en una órbita excéntrica el padre oscila ~2e
+                let libration = 2.0 * orbit.eccentricity + 0.01;
+                assert!(facing < libration, "{} a {} días: {}", body.name, days, facing);
+
+                // El polo queda perpendicular al plano de la órbita
+                let pole = spin.rotate(Vec3::up());
+                assert!(pole.dot(to_parent).abs() < 1e-3, "{} a {} días", body.name, days);
+            }
+        }
+    }
+}
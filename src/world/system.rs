@@ -1,193 +1,322 @@
 use std::f32::consts::PI;
 
+use crate::calendar;
 use crate::camera::Camera;
-use crate::math::Vec3;
-use crate::renderer::Renderer;
+use crate::math::{ray_sphere, Quat, Vec3};
+use crate::renderer::{Renderer, SphereSurface, Spin};
 
-use super::{Body, BodyKind};
+use super::nbody::{self, G};
+use super::{Body, BodyKind, Diagnostics, Drift, Integrator, NBody, Orbit, OrbitalElements};
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
 
 pub struct SolarSystem {
     pub bodies: Vec<Body>,
+    /// Tiempo de simulación en segundos desde la época de los elementos orbitales
+    pub time: f64,
+    /// Día juliano (UTC) de la época: la fecha del calendario en t = 0
+    pub epoch: f64,
+    /// Modo de gravedad mutua; con `None` los cuerpos siguen sus órbitas de Kepler
+    pub nbody: Option<NBody>,
+    /// Energía y momento angular tras el último paso
+    pub diagnostics: Diagnostics,
+    /// Los mismos valores al cargar la escena o al cambiar de modo, para medir la deriva
+    pub baseline: Diagnostics,
 }
 
 impl SolarSystem {
-    pub fn new_demo() -> Self {
-        let mut bodies = Vec::new();
-
-        // Sol (0)
-        bodies.push(Body {
-            name: "Sol".into(),
-            kind: BodyKind::Star,
-            radius: 8.0,
-            color: 0xFFFFD27F,
-            orbit_radius: 0.0,
-            orbit_speed: 0.0,
-            angle: 0.0,
-            parent: None,
-        });
-
-        // Planeta 1 (1)
-        bodies.push(Body {
-            name: "Mercury".into(),
-            kind: BodyKind::Planet,
-            radius: 3.2,
-            color: 0xFF5CC8FF,
-            orbit_radius: 25.0,
-            orbit_speed: 0.12,
-            angle: 0.0,
-            parent: Some(0),
-        });
-
-        // Planeta 2 (2)
-        bodies.push(Body {
-            name: "Venus".into(),
-            kind: BodyKind::Planet,
-            radius: 4.5,
-            color: 0xFFFF7AC8,
-            orbit_radius: 50.0,
-            orbit_speed: 0.32,
-            angle: PI / 3.0,
-            parent: Some(0),
-        });
-
-        // Planeta 3 (3)
-        bodies.push(Body {
-            name: "Super Earth (Our Home)".into(),
-            kind: BodyKind::Planet,
-            radius: 5.4,
-            color: 0xFF8DFF8D,
-            orbit_radius: 70.0,
-            orbit_speed: 0.54,
-            angle: PI / 2.0,
-            parent: Some(0),
-        });
-
-        // Luna de Verdania (4)
-        bodies.push(Body {
-            name: "Super Moon".into(),
-            kind: BodyKind::Moon,
-            radius: 1.8,
-            color: 0xFFCFEFFF,
-            orbit_radius: 10.0,
-            orbit_speed: 2.0,
-            angle: PI / 4.0,
-            parent: Some(3),
-        });
-
-        bodies.push(Body {
-            name: "Mars".into(),
-            kind: BodyKind::Planet,
-            radius: 6.0,
-            color: 0xFFCFEFFF,
-            orbit_radius: 90.0,
-            orbit_speed: 1.0,
-            angle: PI / 4.0,
-            parent: Some(0),
-        });
-
-
-        SolarSystem { bodies }
+    pub fn new(bodies: Vec<Body>) -> Self {
+        let mut system = Self {
+            bodies,
+            time: 0.0,
+            epoch: calendar::J2000,
+            nbody: None,
+            diagnostics: Diagnostics::default(),
+            baseline: Diagnostics::default(),
+        };
+        system.reset_baseline();
+        system
     }
 
     pub fn update(&mut self, dt: f32) {
-        for b in &mut self.bodies {
-            b.update(dt);
+        self.time += dt as f64;
+        if let Some(nbody) = &mut self.nbody {
+            nbody.step(dt);
+        }
+        self.diagnostics = self.compute_diagnostics();
+    }
+
+    /// Día juliano actual: avanza con el tiempo de simulación
+    pub fn date(&self) -> f64 {
+        self.epoch + self.time / SECONDS_PER_DAY
+    }
+
+    /// Lleva la simulación al día juliano `jd`. Con gravedad mutua no se puede
+    /// integrar hasta allí: se vuelve a arrancar desde las órbitas de Kepler
+    /// en esa fecha.
+    pub fn set_date(&mut self, jd: f64) {
+        let integrator = self.nbody.take().map(|n| n.integrator);
+        self.time = (jd - self.epoch) * SECONDS_PER_DAY;
+        match integrator {
+            Some(integrator) => self.start_nbody(integrator),
+            None => self.reset_baseline(),
+        }
+    }
+
+    /// Pasa al modo N-cuerpos partiendo de las posiciones y velocidades actuales
+    pub fn start_nbody(&mut self, integrator: Integrator) {
+        self.nbody = None;
+        self.nbody = Some(NBody::from_system(self, integrator));
+        self.reset_baseline();
+    }
+
+    /// Vuelve a las órbitas de Kepler: los cuerpos saltan a donde estarían en
+    /// el tiempo actual sin la gravedad mutua
+    pub fn stop_nbody(&mut self) {
+        self.nbody = None;
+        self.reset_baseline();
+    }
+
+    /// Toma el estado actual como referencia de la deriva
+    pub fn reset_baseline(&mut self) {
+        self.diagnostics = self.compute_diagnostics();
+        self.baseline = self.diagnostics;
+    }
+
+    /// Cuánto se alejaron la energía y el momento angular desde la referencia.
+    /// Solo tiene sentido con gravedad mutua: las órbitas de Kepler no
+    /// conservan la energía del sistema completo.
+    pub fn drift(&self) -> Drift {
+        self.diagnostics.drift_from(&self.baseline)
+    }
+
+    /// Energía cinética, potencial (de todos los pares) y momento angular
+    /// total respecto al origen
+    pub fn compute_diagnostics(&self) -> Diagnostics {
+        let n = self.bodies.len();
+        let positions: Vec<Vec3> = (0..n).map(|i| self.body_position(i)).collect();
+        let velocities: Vec<Vec3> = (0..n).map(|i| self.body_velocity(i)).collect();
+
+        let mut d = Diagnostics::default();
+        for i in 0..n {
+            let m = self.bodies[i].mass as f64;
+            let (r, v) = (positions[i], velocities[i]);
+            let (rx, ry, rz) = (r.x as f64, r.y as f64, r.z as f64);
+            let (vx, vy, vz) = (v.x as f64, v.y as f64, v.z as f64);
+
+            d.kinetic += 0.5 * m * (vx * vx + vy * vy + vz * vz);
+            d.angular_momentum[0] += m * (ry * vz - rz * vy);
+            d.angular_momentum[1] += m * (rz * vx - rx * vz);
+            d.angular_momentum[2] += m * (rx * vy - ry * vx);
+
+            for (j, other) in self.bodies.iter().enumerate().skip(i + 1) {
+                let dist = (positions[j] - r).length();
+                let contact = self.bodies[i].radius + other.radius;
+                d.potential += nbody::pair_potential(self.bodies[i].mass, other.mass, dist, contact);
+            }
+        }
+        d
+    }
+
+    /// Elementos osculantes del cuerpo respecto a su padre (`None` si no tiene)
+    pub fn orbital_elements(&self, index: usize) -> Option<OrbitalElements> {
+        let b = &self.bodies[index];
+        let parent = b.parent?;
+        let mu = self.orbit_mu(index, b.orbit.as_ref())?;
+
+        let r = self.body_position(index) - self.body_position(parent);
+        let v = self.body_velocity(index) - self.body_velocity(parent);
+        Some(OrbitalElements::from_state(r, v, mu))
+    }
+
+    /// Parámetro gravitatorio de la órbita del cuerpo alrededor de su padre:
+    /// G·(M + m), o n²a³ de su órbita si el padre no tiene masa
+    fn orbit_mu(&self, index: usize, orbit: Option<&Orbit>) -> Option<f32> {
+        let b = &self.bodies[index];
+        let parent_mass = b.parent.map_or(0.0, |p| self.bodies[p].mass);
+        if parent_mass > 0.0 {
+            Some(G * (parent_mass + b.mass))
+        } else {
+            orbit.map(|o| o.mean_motion * o.mean_motion * o.semi_major_axis.powi(3))
+        }
+    }
+
+    /// Ángulo de giro actual del cuerpo sobre su propio eje, en [0, 2π)
+    pub fn rotation_angle(&self, index: usize) -> f32 {
+        let b = &self.bodies[index];
+        let mut angle = b.rotation_phase as f64;
+        if b.rotation_period != 0.0 {
+            angle += 2.0 * std::f64::consts::PI * self.time / b.rotation_period as f64;
+        }
+        angle.rem_euclid(2.0 * std::f64::consts::PI) as f32
+    }
+
+    /// Orientación actual del cuerpo i (ver `Spin`)
+    pub fn body_spin(&self, index: usize) -> Spin {
+        let b = &self.bodies[index];
+        match &b.orbit {
+            Some(orbit) if b.tidally_locked => {
+                let phase = Quat::from_axis_angle(Vec3::up(), b.rotation_phase);
+                Spin { orientation: orbit.locked_orientation(self.time) * phase }
+            }
+            _ => Spin::tilted(self.rotation_angle(index), b.axial_tilt),
         }
     }
 
     /// Posición global del cuerpo i
     pub fn body_position(&self, index: usize) -> Vec3 {
+        if let Some(nbody) = &self.nbody {
+            return nbody.positions[index];
+        }
+
         let b = &self.bodies[index];
+        if let Some(free) = &b.free {
+            return free.position + free.velocity * self.time as f32;
+        }
 
-        match b.parent {
-            None => match b.kind {
-                BodyKind::Star => Vec3::zero(),
-                BodyKind::Planet | BodyKind::Moon => {
-                    let x = b.orbit_radius * b.angle.cos();
-                    let z = b.orbit_radius * b.angle.sin();
-                    Vec3::new(x, 0.0, z)
-                }
-            },
-            Some(parent_idx) => {
-                let parent_pos = self.body_position(parent_idx);
-                if b.orbit_radius == 0.0 {
-                    parent_pos
-                } else {
-                    let x = b.orbit_radius * b.angle.cos();
-                    let z = b.orbit_radius * b.angle.sin();
-                    parent_pos + Vec3::new(x, 0.0, z)
-                }
-            }
+        let parent_pos = match b.parent {
+            None => Vec3::zero(),
+            Some(parent_idx) => self.body_position(parent_idx),
+        };
+
+        match &b.orbit {
+            None => parent_pos,
+            Some(orbit) => parent_pos + orbit.position_at(self.time),
+        }
+    }
+
+    /// Velocidad global del cuerpo i. En modo Kepler es la que mantiene la
+    /// forma de su órbita alrededor del padre con μ = G·(M + m); si el padre no
+    /// tiene masa, se usa n²a³ para no alterar la órbita.
+    pub fn body_velocity(&self, index: usize) -> Vec3 {
+        if let Some(nbody) = &self.nbody {
+            return nbody.velocities[index];
         }
+
+        let b = &self.bodies[index];
+        if let Some(free) = &b.free {
+            return free.velocity;
+        }
+
+        let parent_vel = match b.parent {
+            None => Vec3::zero(),
+            Some(parent_idx) => self.body_velocity(parent_idx),
+        };
+
+        match (&b.orbit, self.orbit_mu(index, b.orbit.as_ref())) {
+            (Some(orbit), Some(mu)) => parent_vel + orbit.velocity_at(self.time, mu),
+            _ => parent_vel,
+        }
+    }
+
+    /// Posición global de la primera estrella del sistema (la fuente de luz)
+    pub fn star_position(&self) -> Option<Vec3> {
+        self.bodies
+            .iter()
+            .position(|b| matches!(b.kind, BodyKind::Star))
+            .map(|i| self.body_position(i))
+    }
+
+    /// Cuerpo cuya superficie está más cerca de `point`, con esa distancia
+    /// (negativa si el punto está dentro del cuerpo)
+    pub fn nearest_body(&self, point: Vec3) -> Option<(usize, f32)> {
+        (0..self.bodies.len())
+            .map(|i| {
+                let dist = (self.body_position(i) - point).length() - self.bodies[i].radius;
+                (i, dist)
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Primer cuerpo que toca el rayo (`dir` normalizado) y la distancia al impacto
+    pub fn pick_body(&self, origin: Vec3, dir: Vec3) -> Option<(usize, f32)> {
+        (0..self.bodies.len())
+            .filter_map(|i| {
+                ray_sphere(origin, dir, self.body_position(i), self.bodies[i].radius)
+                    .map(|t| (i, t))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
     }
 
-    /// Posición en pantalla + radio del cuerpo `index`, para dibujar la esfera texturizada
+    /// Posición en pantalla + radio aparente en píxeles del cuerpo `index`
     pub fn project_body(
         &self,
         index: usize,
         renderer: &Renderer,
         camera: &Camera,
     ) -> Option<((i32, i32), i32)> {
-        let b = &self.bodies[index];
-        let center_world = self.body_position(index);
-
-        if let Some((sx, sy)) = renderer.project_point(center_world, camera) {
-            let sample_world = center_world + Vec3::new(b.radius, 0.0, 0.0);
-            let radius_px = if let Some((sx2, sy2)) = renderer.project_point(sample_world, camera) {
-                let dx = (sx2 - sx) as f32;
-                let dy = (sy2 - sy) as f32;
-                let r = (dx * dx + dy * dy).sqrt() as i32;
-                if r < 2 { 2 } else { r }
-            } else {
-                4
+        let center = self.body_position(index);
+        renderer
+            .project_sphere(center, self.bodies[index].radius, camera)
+            .map(|(screen, radius_px, _)| (screen, radius_px))
+    }
+
+    /// Dibuja cada cuerpo como esfera texturizada e iluminada desde la estrella.
+    pub fn render_bodies(&self, renderer: &mut Renderer, camera: &Camera) {
+        let star_pos = self.star_position();
+
+        // El z-buffer resuelve la visibilidad, no hace falta ordenar los cuerpos
+        for (i, body) in self.bodies.iter().enumerate() {
+            let surface = SphereSurface {
+                day: body.texture.as_deref(),
+                color: body.color,
+                night: body.night_texture.as_deref(),
             };
+            let center = self.body_position(i);
+            let spin = self.body_spin(i);
 
-            Some(((sx, sy), radius_px))
-        } else {
-            None
+            // Las estrellas emiten su propia luz; el resto se ilumina desde la estrella
+            let light = match (body.kind, star_pos) {
+                (BodyKind::Star, _) | (_, None) => None,
+                (_, Some(star)) => Some((star - center).normalized()),
+            };
+
+            renderer.draw_textured_sphere(&surface, center, body.radius, camera, spin, light);
         }
     }
 
-    /// Solo dibuja órbitas (los cuerpos los dibuja App con texturas)
+    /// Solo dibuja órbitas (los cuerpos los dibuja `render_bodies`).
+    /// Las líneas usan el z-buffer, así que deben dibujarse después de los cuerpos
+    /// para quedar ocultas detrás de ellos.
+    /// Con gravedad mutua las elipses de Kepler ya no describen el movimiento
+    /// y se dibujan las osculantes (solo las cerradas).
     pub fn render(&self, renderer: &mut Renderer, camera: &Camera) {
         let orbit_color_planet = 0xFF20254F;
         let orbit_color_moon = 0xFF303B7A;
 
-        for b in &self.bodies {
-            match b.kind {
-                BodyKind::Planet | BodyKind::Moon => {
-                    if b.orbit_radius <= 0.0 {
-                        continue;
-                    }
-
-                    let center_world = match b.parent {
-                        None => Vec3::zero(),
-                        Some(parent_idx) => self.body_position(parent_idx),
-                    };
-
-                    let segments = 64;
-                    let mut prev: Option<(i32, i32)> = None;
-
-                    for s in 0..=segments {
-                        let t = s as f32 / segments as f32 * 2.0 * PI;
-                        let x = center_world.x + b.orbit_radius * t.cos();
-                        let z = center_world.z + b.orbit_radius * t.sin();
-                        let world = Vec3::new(x, center_world.y, z);
-
-                        if let Some(screen) = renderer.project_point(world, camera) {
-                            if let Some(prev_pt) = prev {
-                                let col = match b.kind {
-                                    BodyKind::Planet => orbit_color_planet,
-                                    BodyKind::Moon => orbit_color_moon,
-                                    _ => orbit_color_planet,
-                                };
-                                renderer.draw_line(prev_pt, screen, col);
-                            }
-                            prev = Some(screen);
-                        }
-                    }
+        for (i, b) in self.bodies.iter().enumerate() {
+            let orbit = match self.nbody {
+                Some(_) => self
+                    .orbital_elements(i)
+                    .and_then(|el| el.to_orbit(self.time)),
+                None => b.orbit.map(|o| o.at(self.time)),
+            };
+            let Some(orbit) = orbit else {
+                continue;
+            };
+
+            let col = match b.kind {
+                BodyKind::Moon => orbit_color_moon,
+                BodyKind::Planet | BodyKind::Star => orbit_color_planet,
+            };
+
+            let center_world = match b.parent {
+                None => Vec3::zero(),
+                Some(parent_idx) => self.body_position(parent_idx),
+            };
+
+            // Muestreo uniforme en anomalía excéntrica: más puntos cerca del periapsis
+            let segments = 96;
+            let mut prev: Option<Vec3> = None;
+
+            for s in 0..=segments {
+                let e_anomaly = s as f32 / segments as f32 * 2.0 * PI;
+                let world = center_world + orbit.position_at_eccentric_anomaly(e_anomaly);
+
+                if let Some(prev) = prev {
+                    renderer.draw_line_depth(prev, world, camera, col);
                 }
-                BodyKind::Star => {}
+                prev = Some(world);
             }
         }
     }